@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+/// The known-good answers for each day, read from a manifest like:
+///
+/// ```text
+/// [day08]
+/// part1 = 4647
+/// part2 = 5590
+///
+/// [day10]
+/// part2 = "decdf7d377879877173b7f2fb131cf1b"
+/// ```
+///
+/// Values may be bare numbers or double-quoted strings, and
+/// everything after a `#` outside of quotes is a comment.
+#[derive(Debug, PartialEq)]
+pub struct Answers {
+    expected: BTreeMap<(u32, u8), String>,
+}
+
+/// The result of checking a computed answer against the manifest.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Incorrect { expected: String },
+    Unknown,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut expected = BTreeMap::new();
+        let mut day = None;
+
+        for (idx, raw_line) in text.lines().enumerate() {
+            let line = strip_comment(raw_line).trim();
+            let error = |msg: &str| format!("line {}: {}", idx + 1, msg);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                let name = line.trim_start_matches('[').trim_end_matches(']');
+                if !line.ends_with(']') || !name.starts_with("day") {
+                    return Err(error("expected a section like [day01]"));
+                }
+                day = Some(name[3..].parse::<u32>().map_err(|_| error("couldn't parse day number"))?);
+                continue;
+            }
+
+            let day = day.ok_or_else(|| error("answer given before any [dayNN] section"))?;
+            let idx = line.find('=').ok_or_else(|| error("expected partN = answer"))?;
+            let part = match line[..idx].trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _       => return Err(error("expected part1 or part2")),
+            };
+            let value = line[idx+1..].trim();
+            let value = if value.starts_with('"') {
+                if value.len() < 2 || !value.ends_with('"') {
+                    return Err(error("unterminated string"));
+                }
+                &value[1..value.len() - 1]
+            } else {
+                value
+            };
+
+            if expected.insert((day, part), value.to_string()).is_some() {
+                return Err(error("duplicate answer"));
+            }
+        }
+
+        Ok(Answers { expected })
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<&str> {
+        self.expected.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn check(&self, day: u32, part: u8, actual: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == actual => Verdict::Correct,
+            Some(expected)                       => Verdict::Incorrect { expected: expected.to_string() },
+            None                                 => Verdict::Unknown,
+        }
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (idx, ch) in line.char_indices() {
+        match ch {
+            '"'               => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _                 => {},
+        }
+    }
+
+    line
+}
+
+#[test]
+fn test_parse_answers() {
+    let answers = Answers::parse("# comment\n[day08]\npart1 = 4647 # trailing\npart2 = 5590\n\n[day10]\npart2 = \"de#cd\"").unwrap();
+    assert_eq!(answers.get(8, 1), Some("4647"));
+    assert_eq!(answers.get(8, 2), Some("5590"));
+    assert_eq!(answers.get(10, 1), None);
+    assert_eq!(answers.get(10, 2), Some("de#cd"));
+
+    assert!(Answers::parse("part1 = 3").is_err());
+    assert!(Answers::parse("[day]\npart1 = 3").is_err());
+    assert!(Answers::parse("[day01]\npart3 = 3").is_err());
+    assert!(Answers::parse("[day01]\npart1 = \"3").is_err());
+    assert_eq!(Answers::parse("[day01]\npart1 = 3\npart1 = 4"), Err("line 3: duplicate answer".to_string()));
+}
+
+#[test]
+fn test_check_answers() {
+    let answers = Answers::parse("[day01]\npart1 = 1047").unwrap();
+    assert_eq!(answers.check(1, 1, "1047"), Verdict::Correct);
+    assert_eq!(answers.check(1, 1, "1048"), Verdict::Incorrect { expected: "1047".to_string() });
+    assert_eq!(answers.check(1, 2, "982"), Verdict::Unknown);
+}
@@ -1,10 +1,24 @@
 use std::fs::File;
-use std::io::{Read, Result};
+use std::io::{self, Read, Result};
+use std::path::{Path, PathBuf};
 
-pub fn get_input(filepath: &str) -> Result<String> {
+pub fn get_input<P: AsRef<Path>>(filepath: P) -> Result<String> {
     let mut f = File::open(filepath)?;
     let mut contents = String::new();
     f.read_to_string(&mut contents)?;
 
     Ok(contents)
 }
+
+pub fn get_stdin() -> Result<String> {
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents)?;
+
+    Ok(contents)
+}
+
+/// Resolves a path relative to the root of the crate, so that files
+/// like puzzle inputs are found no matter where the binary is run from.
+pub fn crate_path(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
@@ -1,4 +1,11 @@
-use std::ops::Add;
+use error::{self, AdventError, Result};
+use std::cmp;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+use std::num::TryFromIntError;
+use std::ops::{Add, Mul, Sub};
+use std::result;
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub struct Coord(pub usize, pub usize);
@@ -22,3 +29,594 @@ impl From<Coord> for (usize, usize) {
         (coord.0, coord.1)
     }
 }
+
+/// A signed `(x, y)` point. When points are drawn as a grid, `x` increases
+/// to the right and `y` increases downward, so north is towards negative `y`.
+/// Code that uses `y` the other way around, like day 3's Ulam spiral,
+/// says so and converts when it uses directions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point(pub i64, pub i64);
+
+impl Point {
+    pub fn origin() -> Self {
+        Point(0, 0)
+    }
+
+    /// The number of orthogonal steps between two points.
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.0 - other.0).abs() + (self.1 - other.1).abs()
+    }
+
+    /// The number of steps between two points when diagonal steps are allowed.
+    pub fn chebyshev(self, other: Point) -> i64 {
+        cmp::max((self.0 - other.0).abs(), (self.1 - other.1).abs())
+    }
+
+    /// The four points directly north, east, south and west of this one.
+    pub fn neighbors4(self) -> [Point; 4] {
+        let mut points = [self; 4];
+        for (point, dir) in points.iter_mut().zip(Direction4::ALL.iter()) {
+            *point = self + *dir;
+        }
+        points
+    }
+
+    /// The eight points surrounding this one, clockwise from the north.
+    pub fn neighbors8(self) -> [Point; 8] {
+        let mut points = [self; 8];
+        for (point, dir) in points.iter_mut().zip(Direction8::ALL.iter()) {
+            *point = self + *dir;
+        }
+        points
+    }
+}
+
+impl Add<Point> for Point {
+    type Output = Self;
+
+    fn add(self, other: Point) -> Self {
+        Point(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl Sub<Point> for Point {
+    type Output = Self;
+
+    fn sub(self, other: Point) -> Self {
+        Point(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Self;
+
+    fn mul(self, scale: i64) -> Self {
+        Point(self.0 * scale, self.1 * scale)
+    }
+}
+
+impl Add<Direction4> for Point {
+    type Output = Self;
+
+    fn add(self, dir: Direction4) -> Self {
+        self + dir.delta()
+    }
+}
+
+impl Add<Direction8> for Point {
+    type Output = Self;
+
+    fn add(self, dir: Direction8) -> Self {
+        self + dir.delta()
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from(tuple: (i64, i64)) -> Self {
+        Point(tuple.0, tuple.1)
+    }
+}
+
+impl From<Point> for (i64, i64) {
+    fn from(point: Point) -> (i64, i64) {
+        (point.0, point.1)
+    }
+}
+
+impl From<Coord> for Point {
+    fn from(coord: Coord) -> Self {
+        Point(coord.0 as i64, coord.1 as i64)
+    }
+}
+
+impl TryFrom<Point> for Coord {
+    type Error = TryFromIntError;
+
+    /// Fails if either coordinate is negative.
+    fn try_from(point: Point) -> result::Result<Self, TryFromIntError> {
+        Ok(Coord(usize::try_from(point.0)?, usize::try_from(point.1)?))
+    }
+}
+
+/// One of the four orthogonal directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    North, East, South, West
+}
+
+impl Direction4 {
+    /// Every direction, clockwise from the north.
+    pub const ALL: [Direction4; 4] = [Direction4::North, Direction4::East, Direction4::South, Direction4::West];
+
+    /// The point one step away from the origin in this direction.
+    pub fn delta(self) -> Point {
+        match self {
+            Direction4::North => Point(0, -1),
+            Direction4::East  => Point(1, 0),
+            Direction4::South => Point(0, 1),
+            Direction4::West  => Point(-1, 0),
+        }
+    }
+
+    /// The direction of a single orthogonal step, if `delta` is one.
+    pub fn from_delta(delta: Point) -> Option<Self> {
+        Self::ALL.iter().cloned().find(|dir| dir.delta() == delta)
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// One of the eight compass directions, including the diagonals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from the north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North, Direction8::NorthEast, Direction8::East, Direction8::SouthEast,
+        Direction8::South, Direction8::SouthWest, Direction8::West, Direction8::NorthWest,
+    ];
+
+    /// The point one step away from the origin in this direction.
+    pub fn delta(self) -> Point {
+        match self {
+            Direction8::North     => Point(0, -1),
+            Direction8::NorthEast => Point(1, -1),
+            Direction8::East      => Point(1, 0),
+            Direction8::SouthEast => Point(1, 1),
+            Direction8::South     => Point(0, 1),
+            Direction8::SouthWest => Point(-1, 1),
+            Direction8::West      => Point(-1, 0),
+            Direction8::NorthWest => Point(-1, -1),
+        }
+    }
+
+    /// The direction of a single step, if `delta` is one.
+    pub fn from_delta(delta: Point) -> Option<Self> {
+        Self::ALL.iter().cloned().find(|dir| dir.delta() == delta)
+    }
+
+    /// Turns 45 degrees anticlockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(dir: Direction4) -> Self {
+        Direction8::ALL[dir as usize * 2]
+    }
+}
+
+/// The smallest rectangle containing a set of positions. Both corners
+/// are inclusive; bounds with `max` less than `min` are empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn empty() -> Self {
+        Bounds { min: Point(0, 0), max: Point(-1, -1) }
+    }
+
+    /// The bounds of a `width` by `height` rectangle with its top-left corner at the origin.
+    pub fn sized(width: usize, height: usize) -> Self {
+        Bounds { min: Point(0, 0), max: Point(width as i64 - 1, height as i64 - 1) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max.0 < self.min.0 || self.max.1 < self.min.1
+    }
+
+    pub fn width(&self) -> usize {
+        cmp::max(self.max.0 - self.min.0 + 1, 0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        cmp::max(self.max.1 - self.min.1 + 1, 0) as usize
+    }
+
+    pub fn contains(&self, pos: Point) -> bool {
+        self.min.0 <= pos.0 && pos.0 <= self.max.0 && self.min.1 <= pos.1 && pos.1 <= self.max.1
+    }
+
+    /// Grows the bounds, if necessary, so that they contain `pos`.
+    pub fn include(&mut self, pos: Point) {
+        if self.is_empty() {
+            *self = Bounds { min: pos, max: pos };
+        } else {
+            self.min = Point(cmp::min(self.min.0, pos.0), cmp::min(self.min.1, pos.1));
+            self.max = Point(cmp::max(self.max.0, pos.0), cmp::max(self.max.1, pos.1));
+        }
+    }
+
+    /// Every position within the bounds, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let Bounds { min, max } = *self;
+        (min.1..max.1 + 1).flat_map(move |y| (min.0..max.0 + 1).map(move |x| Point(x, y)))
+    }
+
+    /// Where `pos` lives in a row-by-row vector covering the bounds.
+    fn index_of(&self, pos: Point) -> Option<usize> {
+        if self.contains(pos) {
+            Some((pos.1 - self.min.1) as usize * self.width() + (pos.0 - self.min.0) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Storage<T> {
+    Dense(Vec<T>),
+    Sparse(HashMap<Point, T>),
+}
+
+/// A two-dimensional grid of values.
+///
+/// A dense grid has a fixed size and a value at every position within it,
+/// stored row by row. A sparse grid only stores the positions that have
+/// been set, and its bounds grow to cover them; it suits grids that are
+/// mostly empty, or that spread out in every direction.
+///
+/// Either way, `get` returns `None` for positions without a value, and the
+/// row, column and neighbour views only ever look within the bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    bounds: Bounds,
+    storage: Storage<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a `width` by `height` dense grid with every position set to `fill`.
+    pub fn dense(width: usize, height: usize, fill: T) -> Self where T: Clone {
+        Grid { bounds: Bounds::sized(width, height), storage: Storage::Dense(vec![fill; width * height]) }
+    }
+
+    /// Creates an empty sparse grid.
+    pub fn sparse() -> Self {
+        Grid { bounds: Bounds::empty(), storage: Storage::Sparse(HashMap::new()) }
+    }
+
+    /// Creates a dense grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (idx, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(AdventError::parse(format!("expected a row of {} cells", width)).on_line(idx + 1));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { bounds: Bounds::sized(width, height), storage: Storage::Dense(cells) })
+    }
+
+    /// Parses a dense grid with one row per line and one cell per character,
+    /// using `cell` to turn each character into a value.
+    pub fn parse<F>(text: &str, mut cell: F) -> Result<Self>
+        where F: FnMut(char) -> Result<T>
+    {
+        let rows = error::parse_lines(text.trim_end_matches('\n'), |line| {
+            line.char_indices().map(|(idx, ch)| cell(ch).map_err(|err| err.at(line, &line[idx..]))).collect()
+        })?;
+        Self::from_rows(rows)
+    }
+
+    /// Parses a sparse grid with one row per line and one cell per character.
+    /// Characters for which `cell` returns `None` are left empty, and rows
+    /// may be of different lengths.
+    pub fn parse_sparse<F>(text: &str, mut cell: F) -> Result<Self>
+        where F: FnMut(char) -> Result<Option<T>>
+    {
+        let mut grid = Self::sparse();
+        for (y, line) in text.trim_end_matches('\n').lines().enumerate() {
+            for (x, (idx, ch)) in line.char_indices().enumerate() {
+                let value = cell(ch).map_err(|err| err.at(line, &line[idx..]).on_line(y + 1))?;
+                if let Some(value) = value {
+                    grid.set(Point(x as i64, y as i64), value);
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds.height()
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        match self.storage {
+            Storage::Dense(ref cells)  => self.bounds.index_of(pos).map(|idx| &cells[idx]),
+            Storage::Sparse(ref cells) => cells.get(&pos),
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        match self.storage {
+            Storage::Dense(ref mut cells)  => self.bounds.index_of(pos).map(move |idx| &mut cells[idx]),
+            Storage::Sparse(ref mut cells) => cells.get_mut(&pos),
+        }
+    }
+
+    /// Stores `value` at `pos`. Sparse grids grow to fit; dense grids
+    /// panic if `pos` is outside their bounds.
+    pub fn set(&mut self, pos: Point, value: T) {
+        let bounds = self.bounds;
+        match self.storage {
+            Storage::Dense(ref mut cells) => {
+                let idx = bounds.index_of(pos)
+                    .unwrap_or_else(|| panic!("{:?} is outside the grid's bounds {:?}", pos, bounds));
+                cells[idx] = value;
+            },
+            Storage::Sparse(ref mut cells) => {
+                self.bounds.include(pos);
+                cells.insert(pos, value);
+            },
+        }
+    }
+
+    /// Swaps the values at two positions, which must both be set.
+    pub fn swap(&mut self, a: Point, b: Point) {
+        if a == b {
+            return;
+        }
+        match self.storage {
+            Storage::Dense(ref mut cells) => {
+                match (self.bounds.index_of(a), self.bounds.index_of(b)) {
+                    (Some(a), Some(b)) => cells.swap(a, b),
+                    _                  => panic!("can't swap {:?} and {:?} outside {:?}", a, b, self.bounds),
+                }
+            },
+            Storage::Sparse(ref mut cells) => {
+                if !cells.contains_key(&a) || !cells.contains_key(&b) {
+                    panic!("can't swap {:?} and {:?} unless both are set", a, b);
+                }
+                let value_a = cells.remove(&a).unwrap();
+                let value_b = cells.insert(b, value_a).unwrap();
+                cells.insert(a, value_b);
+            },
+        }
+    }
+
+    /// Every position that has a value, along with the value. Dense grids
+    /// are traversed in reading order; sparse grids in no particular order.
+    pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = (Point, &'a T)> + 'a> {
+        match self.storage {
+            Storage::Dense(ref cells)  => Box::new(self.bounds.positions().zip(cells.iter())),
+            Storage::Sparse(ref cells) => Box::new(cells.iter().map(|(&pos, value)| (pos, value))),
+        }
+    }
+
+    /// The up to four values directly above, below, left and right of `pos`.
+    pub fn neighbors4<'a>(&'a self, pos: Point) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        let positions = pos.neighbors4();
+        (0..positions.len()).filter_map(move |idx| self.get(positions[idx]).map(|value| (positions[idx], value)))
+    }
+
+    /// The up to eight values surrounding `pos`, including the diagonals.
+    pub fn neighbors8<'a>(&'a self, pos: Point) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        let positions = pos.neighbors8();
+        (0..positions.len()).filter_map(move |idx| self.get(positions[idx]).map(|value| (positions[idx], value)))
+    }
+
+    /// The cells of row `y`, from left to right across the grid's bounds.
+    pub fn row(&self, y: i64) -> Line<'_, T> {
+        Line { grid: self, next: Point(self.bounds.min.0, y), step: Point(1, 0), remaining: self.width() }
+    }
+
+    /// The cells of column `x`, from top to bottom across the grid's bounds.
+    pub fn column(&self, x: i64) -> Line<'_, T> {
+        Line { grid: self, next: Point(x, self.bounds.min.1), step: Point(0, 1), remaining: self.height() }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Line<'_, T>> {
+        (self.bounds.min.1..self.bounds.max.1 + 1).map(move |y| self.row(y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = Line<'_, T>> {
+        (self.bounds.min.0..self.bounds.max.0 + 1).map(move |x| self.column(x))
+    }
+
+    /// Draws the grid as text, one line per row, using `cell` to pick
+    /// the character for each position (empty positions get `None`).
+    pub fn render<F>(&self, mut cell: F) -> String
+        where F: FnMut(Option<&T>) -> char
+    {
+        self.rows().map(|row| row.map(&mut cell).collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl<T: Hash> Hash for Grid<T> {
+    /// Hashes the contents in reading order, so that equal grids hash the
+    /// same regardless of a sparse grid's insertion order.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bounds.hash(state);
+        for pos in self.bounds.positions() {
+            self.get(pos).hash(state);
+        }
+    }
+}
+
+/// A view of one row or column of a `Grid`, yielding the value at each
+/// position along it, or `None` where a sparse grid has no value.
+pub struct Line<'a, T: 'a> {
+    grid: &'a Grid<T>,
+    next: Point,
+    step: Point,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Line<'a, T> {
+    type Item = Option<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = self.grid.get(self.next);
+        self.next = self.next + self.step;
+        self.remaining -= 1;
+        Some(value)
+    }
+}
+
+#[test]
+fn test_point() {
+    let (a, b) = (Point(1, -2), Point(-3, 4));
+    assert_eq!(a + b, Point(-2, 2));
+    assert_eq!(a - b, Point(4, -6));
+    assert_eq!(a * 3, Point(3, -6));
+    assert_eq!(a.manhattan(b), 10);
+    assert_eq!(a.chebyshev(b), 6);
+    assert_eq!(Point::origin().neighbors4(), [Point(0, -1), Point(1, 0), Point(0, 1), Point(-1, 0)]);
+    assert_eq!(Point::origin().neighbors8().iter().filter(|p| p.chebyshev(Point::origin()) == 1).count(), 8);
+
+    assert_eq!(Point::from(Coord(2, 3)), Point(2, 3));
+    assert_eq!(Coord::try_from(Point(2, 3)), Ok(Coord(2, 3)));
+    assert!(Coord::try_from(Point(2, -3)).is_err());
+}
+
+#[test]
+fn test_directions() {
+    use self::Direction4::*;
+    assert_eq!(North.turn_left(), West);
+    assert_eq!(West.turn_right(), North);
+    assert_eq!(East.opposite(), West);
+    assert_eq!(Point(3, 3) + South, Point(3, 4));
+    assert_eq!(Direction4::from_delta(Point(-1, 0)), Some(West));
+    assert_eq!(Direction4::from_delta(Point(1, 1)), None);
+
+    assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+    assert_eq!(Direction8::NorthWest.turn_right(), Direction8::North);
+    assert_eq!(Direction8::SouthEast.opposite(), Direction8::NorthWest);
+    assert_eq!(Direction8::from_delta(Point(1, 1)), Some(Direction8::SouthEast));
+    assert_eq!(Direction8::from(West), Direction8::West);
+}
+
+#[test]
+fn test_bounds() {
+    let mut bounds = Bounds::empty();
+    assert!(bounds.is_empty());
+    assert_eq!((bounds.width(), bounds.height()), (0, 0));
+    bounds.include(Point(2, -1));
+    bounds.include(Point(-1, 3));
+    assert_eq!(bounds, Bounds { min: Point(-1, -1), max: Point(2, 3) });
+    assert_eq!((bounds.width(), bounds.height()), (4, 5));
+    assert!(bounds.contains(Point(0, 0)));
+    assert!(!bounds.contains(Point(3, 0)));
+    assert_eq!(Bounds::sized(2, 2).positions().collect::<Vec<_>>(), vec![Point(0, 0), Point(1, 0), Point(0, 1), Point(1, 1)]);
+}
+
+#[test]
+fn test_dense_grid() {
+    let mut grid = Grid::parse("#..\n.#.\n", |ch| match ch {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _   => Err(AdventError::parse("expected # or .")),
+    }).unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.get(Point(1, 1)), Some(&true));
+    assert_eq!(grid.get(Point(3, 0)), None);
+
+    grid.set(Point(2, 1), true);
+    grid.swap(Point(0, 0), Point(0, 1));
+    let render = |grid: &Grid<bool>| grid.render(|cell| if cell == Some(&true) { '#' } else { '.' });
+    assert_eq!(render(&grid), "...\n###");
+
+    let column: Vec<_> = grid.column(0).collect();
+    assert_eq!(column, vec![Some(&false), Some(&true)]);
+    assert_eq!(grid.rows().count(), 2);
+    assert_eq!(grid.columns().count(), 3);
+    assert_eq!(grid.iter().filter(|&(_, &on)| on).count(), 3);
+    grid.swap(Point(1, 1), Point(1, 1));
+    assert_eq!(render(&grid), "...\n###");
+
+    let err = Grid::parse("..\n.x", |ch| if ch == '.' { Ok(ch) } else { Err(AdventError::parse("expected .")) }).unwrap_err();
+    assert_eq!(err.to_string(), "line 2, column 2: expected .");
+    let err = Grid::parse("..\n...", Ok).unwrap_err();
+    assert_eq!(err.to_string(), "line 2: expected a row of 2 cells");
+}
+
+#[test]
+fn test_sparse_grid() {
+    let mut grid = Grid::parse_sparse(" a\nb  c", |ch| Ok(if ch == ' ' { None } else { Some(ch) })).unwrap();
+    assert_eq!(grid.bounds(), Bounds { min: Point(0, 0), max: Point(3, 1) });
+    assert_eq!(grid.get(Point(0, 0)), None);
+    assert_eq!(grid.get(Point(3, 1)), Some(&'c'));
+
+    grid.set(Point(-1, -1), 'z');
+    assert_eq!(grid.bounds(), Bounds { min: Point(-1, -1), max: Point(3, 1) });
+    assert_eq!(grid.render(|cell| *cell.unwrap_or(&'.')), "z....\n..a..\n.b..c");
+    assert_eq!(grid.row(1).filter(Option::is_some).count(), 2);
+
+    grid.swap(Point(1, 0), Point(1, 0));
+    assert_eq!(grid.get(Point(1, 0)), Some(&'a'));
+    grid.swap(Point(1, 0), Point(0, 1));
+    assert_eq!((grid.get(Point(1, 0)), grid.get(Point(0, 1))), (Some(&'b'), Some(&'a')));
+
+    let swapped = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| grid.swap(Point(0, 1), Point(2, 0))));
+    assert!(swapped.is_err());
+    assert_eq!((grid.get(Point(0, 1)), grid.get(Point(2, 0))), (Some(&'a'), None));
+}
+
+#[test]
+fn test_grid_neighbors() {
+    let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]).unwrap();
+    let sum4: i32 = grid.neighbors4(Point(1, 1)).map(|(_, &n)| n).sum();
+    let sum8: i32 = grid.neighbors8(Point(1, 1)).map(|(_, &n)| n).sum();
+    assert_eq!((sum4, sum8), (2 + 4 + 6 + 8, 45 - 5));
+    assert_eq!(grid.neighbors4(Point(0, 0)).map(|(pos, _)| pos).collect::<Vec<_>>(), vec![Point(1, 0), Point(0, 1)]);
+    assert_eq!(grid.neighbors8(Point(2, 2)).count(), 3);
+}
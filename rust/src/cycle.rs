@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of a sequence of states, each made from the last by a step
+/// function: after `prefix` steps, the sequence reaches a state that then
+/// comes back every `length` steps.
+///
+/// Any sequence with finitely many states eventually repeats, and the
+/// finders below all give the same answer for it; they never return if
+/// the sequence doesn't repeat.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The smallest number of steps that lands on the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix + self.length {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+
+    /// The state after `n` steps from `start`, without taking all of them.
+    pub fn state_after<S, F>(&self, start: S, mut step: F, n: usize) -> S
+        where F: FnMut(&S) -> S
+    {
+        (0..self.reduce(n)).fold(start, |state, _| step(&state))
+    }
+}
+
+/// Floyd's tortoise and hare: the hare runs at twice the tortoise's
+/// speed until they meet somewhere inside the cycle.
+pub fn floyd<S, F>(start: S, mut step: F) -> Cycle
+    where S: Clone + Eq, F: FnMut(&S) -> S
+{
+    let mut tortoise = step(&start);
+    let mut hare = {
+        let once = step(&start);
+        step(&once)
+    };
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let once = step(&hare);
+        hare = step(&once);
+    }
+
+    // The meeting point is a multiple of the cycle length from the start,
+    // so moving both at the same speed, one from the start, they next
+    // meet where the cycle begins.
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// Brent's algorithm: the tortoise teleports to the hare every power of
+/// two steps, which finds the cycle length directly and usually takes
+/// fewer steps than Floyd's.
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+    where S: Clone + Eq, F: FnMut(&S) -> S
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare a cycle length ahead of the tortoise, they
+    // meet where the cycle begins.
+    let mut prefix = 0;
+    tortoise = start.clone();
+    hare = (0..length).fold(start, |state, _| step(&state));
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// Remembers the step at which each state was first seen, stopping at
+/// the first state seen twice. Unlike `floyd` and `brent`, which only hold
+/// a couple of states at a time, this steps through the sequence just once.
+pub fn hashed<S, F>(start: S, mut step: F) -> Cycle
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> S
+{
+    let mut seen_at: HashMap<S, usize> = HashMap::new();
+    let mut state = start;
+    let mut steps = 0;
+
+    loop {
+        if let Some(first) = seen_at.insert(state.clone(), steps) {
+            return Cycle { prefix: first, length: steps - first };
+        }
+        state = step(&state);
+        steps += 1;
+    }
+}
+
+#[test]
+fn test_cycle_finders() {
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 3 -> ...
+    let next = [1, 2, 3, 4, 5, 3];
+    let step = |&n: &usize| next[n];
+    let expected = Cycle { prefix: 3, length: 3 };
+
+    assert_eq!(floyd(0, step), expected);
+    assert_eq!(brent(0, step), expected);
+    assert_eq!(hashed(0, step), expected);
+    assert_eq!(floyd(3, step), Cycle { prefix: 0, length: 3 });
+    assert_eq!(brent(5, step), Cycle { prefix: 0, length: 3 });
+    assert_eq!(hashed(0, |&n: &usize| n), Cycle { prefix: 0, length: 1 });
+}
+
+#[test]
+fn test_state_after() {
+    let next = [1, 2, 3, 4, 5, 3];
+    let step = |&n: &usize| next[n];
+    let cycle = Cycle { prefix: 3, length: 3 };
+
+    assert_eq!(cycle.reduce(4), 4);
+    assert_eq!(cycle.reduce(10), 4);
+    assert_eq!(cycle.state_after(0, step, 2), 2);
+    assert_eq!(cycle.state_after(0, step, 10), 4);
+    assert_eq!(cycle.state_after(0, step, 1_000_000_000), 4);
+}
@@ -1,9 +1,32 @@
+#[cfg(test)]
 use common;
+use error::{AdventError, Result};
+use solution::Solution;
 
-pub fn run(_args: &[String]) {
-    let input: String = common::get_input("./inputs/01.txt").expect("expected input 01.txt");
-    println!("Part 1: {}", get_sum(&input));
-    println!("Part 2: {}", get_sum_matching_index(&input, |s, i| s.len() / 2 + i));
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+
+    type Input = String;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<String> {
+        let digits = input.trim();
+        match digits.char_indices().find(|&(_, ch)| !ch.is_ascii_digit()) {
+            Some((idx, ch)) => Err(AdventError::parse(format!("expected a digit, found '{}'", ch)).at(input, &digits[idx..])),
+            None            => Ok(digits.to_string()),
+        }
+    }
+
+    fn part1(&self, input: &String) -> Result<u32> {
+        Ok(get_sum(input))
+    }
+
+    fn part2(&self, input: &String) -> Result<u32> {
+        Ok(get_sum_matching_index(input, |s, i| s.len() / 2 + i))
+    }
 }
 
 fn get_sum(input: &str) -> u32 {
@@ -49,3 +72,9 @@ fn test_day01_run() {
     // Day 1 part 1
     assert_eq!(get_sum_matching_index(&common::get_input("./inputs/01.txt").expect("expected input 01.txt"), &jump), 982);
 }
+
+#[test]
+fn test_day01_parse() {
+    assert_eq!(Day01.parse("1234\n").unwrap(), "1234");
+    assert_eq!(Day01.parse("12x4").unwrap_err().column, Some(3));
+}
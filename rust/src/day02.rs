@@ -1,27 +1,53 @@
+#[cfg(test)]
 use common;
+use error::{self, AdventError, Result};
+use solution::Solution;
 use std::str::FromStr;
 
 type Spreadsheet<T> = Vec<Vec<T>>;
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/02.txt").expect("expected input 02.txt");
-    let sheet = text_to_ss(&input);
-    println!("Part 1: {}", checksum(&sheet, row_checksum_hi_lo));
-    println!("Part 2: {}", checksum(&sheet, row_checksum_div));
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+
+    type Input = Spreadsheet<i32>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(&self, input: &str) -> Result<Spreadsheet<i32>> {
+        text_to_ss(input)
+    }
+
+    fn part1(&self, sheet: &Spreadsheet<i32>) -> Result<i32> {
+        Ok(checksum(sheet, row_checksum_hi_lo))
+    }
+
+    fn part2(&self, sheet: &Spreadsheet<i32>) -> Result<i32> {
+        if let Some(idx) = sheet.iter().position(|row| find_div(row).is_none()) {
+            return Err(AdventError::solve("could not find evenly divisible numbers").on_line(idx + 1));
+        }
+        Ok(checksum(sheet, row_checksum_div))
+    }
 }
 
-fn text_to_ss(input: &str) -> Spreadsheet<i32> {
-    input.lines().map(|row| {
-        row.split(char::is_whitespace).map(|c| i32::from_str(&c).unwrap()).collect()
-    }).collect()
+fn text_to_ss(input: &str) -> Result<Spreadsheet<i32>> {
+    error::parse_lines(input, |row| {
+        row.split(char::is_whitespace).map(|c| {
+            i32::from_str(&c).map_err(|_| AdventError::parse(format!("couldn't parse '{}' as a number", c)).at(row, c))
+        }).collect()
+    })
 }
 
 #[test]
 fn test_text_to_ss() {
     assert_eq!(
-        text_to_ss("1 2 3\n4 5 6"),
+        text_to_ss("1 2 3\n4 5 6").unwrap(),
         vec![vec![1, 2, 3], vec![4, 5, 6]]
     );
+
+    let err = text_to_ss("1 2 3\n4 x 6").unwrap_err();
+    assert_eq!((err.line, err.column), (Some(2), Some(3)));
 }
 
 fn checksum<F>(sheet: &Spreadsheet<i32>, mut row_checksum: F) -> i32
@@ -34,10 +60,10 @@ fn checksum<F>(sheet: &Spreadsheet<i32>, mut row_checksum: F) -> i32
 
 #[test]
 fn test_checksum() {
-    let sheet1 = text_to_ss("5 1 9 5\n7 5 3\n2 4 6 8");
+    let sheet1 = text_to_ss("5 1 9 5\n7 5 3\n2 4 6 8").unwrap();
     assert_eq!(checksum(&sheet1, row_checksum_hi_lo), 18);
 
-    let sheet2 = text_to_ss("5 9 2 8\n9 4 7 3\n3 8 6 5");
+    let sheet2 = text_to_ss("5 9 2 8\n9 4 7 3\n3 8 6 5").unwrap();
     assert_eq!(checksum(&sheet2, row_checksum_div), 9);
 }
 
@@ -79,7 +105,7 @@ fn test_find_div() {
 
 #[test]
 fn test_day02_run() {
-    let sheet = text_to_ss(&common::get_input("./inputs/02.txt").expect("expected input 02.txt"));
+    let sheet = text_to_ss(&common::get_input("./inputs/02.txt").expect("expected input 02.txt")).unwrap();
     // Part 1
     assert_eq!(checksum(&sheet, row_checksum_hi_lo), 44216);
     // Part 2
@@ -1,200 +1,324 @@
-use common;
-use std::collections::HashMap;
-
-/// An `(x, y)` coordinate pair.
-type Coord = (i32, i32);
+use coords::{Bounds, Direction4, Grid, Point};
+use error::{AdventError, Result};
+use solution::Solution;
+use std::collections::HashSet;
+use std::fmt::Display;
 
 /// A Ulam spiral is a square spiral starting at the origin
-/// and proceeding in a counter-clockwise direction:
+/// and proceeding in a counter-clockwise direction. Unlike most grids,
+/// `y` increases upward:
 ///
-///           -3  -2  -1  0   1   2   3
-///         /--------------------------
-///      3 | 37  36  35  34  33  32  31
-///      2 | 38  17  16  15  14  13  30
-///      1 | 39  18   5   4   3  12  29
-///      0 | 40  19   6   1   2  11  28
-///     -1 | 41  20   7   8   9  10  27
-///     -2 | 42  21  22  23  24  25  26
-///     -3 | 43  44  45  46  47  48  49
+/// ```text
+///       -3  -2  -1  0   1   2   3
+///     /--------------------------
+///  3 | 37  36  35  34  33  32  31
+///  2 | 38  17  16  15  14  13  30
+///  1 | 39  18   5   4   3  12  29
+///  0 | 40  19   6   1   2  11  28
+/// -1 | 41  20   7   8   9  10  27
+/// -2 | 42  21  22  23  24  25  26
+/// -3 | 43  44  45  46  47  48  49
+/// ```
 ///
 /// This implementation of the Ulam spiral allows one to store
 /// values of type `T` at various `(x, y)` coordinates (and fetch the values later),
 /// find all the neighbors of any given coordinate, and traverse the spiral
 /// in the order described above.
-struct Ulam<T: Copy> {
-    explored: HashMap<Coord, T>,
+pub struct Ulam<T: Copy> {
+    explored: Grid<T>,
 }
 
 impl<T: Copy> Ulam<T> {
     /// Creates a new empty Ulam spiral.
-    fn new() -> Self {
-        Ulam { explored: HashMap::new() }
+    pub fn new() -> Self {
+        Ulam { explored: Grid::sparse() }
+    }
+
+    pub fn set(&mut self, coord: Point, value: T) {
+        self.explored.set(coord, value);
+    }
+
+    pub fn get(&self, coord: Point) -> Option<&T> {
+        self.explored.get(coord)
+    }
+
+    /// The eight cells surrounding `coord`.
+    pub fn neighbors(&self, coord: Point) -> Vec<Point> {
+        coord.neighbors8().to_vec()
+    }
+
+    /// The values stored in each of the cells surrounding `coord`.
+    pub fn neighbors_values(&self, coord: Point) -> Vec<Option<&T>> {
+        self.neighbors(coord).iter().map(|&coord| self.explored.get(coord)).collect()
     }
 
-    fn set(&mut self, coord: Coord, value: T) {
-        self.explored.insert(coord, value);
+    /// Traverses the coordinates of the spiral in order, starting at the origin.
+    pub fn iter(&self) -> UlamIterator {
+        UlamIterator::new()
     }
 
-    fn get(&self, coord: Coord) -> Option<&T> {
-        self.explored.get(&coord)
+    /// The coordinate of square `square`, counting the origin as square 1,
+    /// worked out directly rather than by walking the spiral.
+    ///
+    /// Ring `k` around the origin holds squares `(2k - 1)^2 + 1` to
+    /// `(2k + 1)^2`, in four sides of `2k` squares each: up the right,
+    /// leftward along the top, down the left and rightward along the bottom.
+    pub fn position_of(&self, square: u64) -> Point {
+        position_of(square)
     }
 
-    fn neighbors(&self, coord: Coord) -> Vec<Coord> {
-        let (x, y) = coord;
-        let mut v = Vec::with_capacity(8);
+    /// The number of the square at `coord`; the inverse of `position_of`.
+    pub fn square_at(&self, coord: Point) -> u64 {
+        let Point(x, y) = coord;
+        let k = x.abs().max(y.abs());
+        if k == 0 {
+            return 1;
+        }
 
-        for dx in -1..2 {
-            for dy in -1..2 {
-                let cell = (x + dx, y + dy);
-                if cell != coord { // we're not our own neighbor
-                    v.push(cell);
+        let (side, t) = if x == k && y > -k {
+            (0, y + k - 1)
+        } else if y == k {
+            (1, k - 1 - x)
+        } else if x == -k {
+            (2, k - 1 - y)
+        } else {
+            (3, x + k - 1)
+        };
+        ((2 * k - 1).pow(2) + 1 + side * 2 * k + t) as u64
+    }
+
+    /// Draws the cells within `bounds` as a table like the one above,
+    /// with `y` increasing upward. Each cell shows either its stored value
+    /// (or `.` if it has none) or its square number.
+    ///
+    /// If `path_from` is given, the cells on a shortest path from there to
+    /// the origin (across, then up or down) are drawn in brackets.
+    pub fn render(&self, bounds: Bounds, label: UlamLabel, path_from: Option<Point>) -> String where T: Display {
+        let path: HashSet<Point> = match path_from {
+            Some(start) => path_to_origin(start).collect(),
+            None        => HashSet::new(),
+        };
+        let xs: Vec<i64> = (bounds.min.0..bounds.max.0 + 1).collect();
+        let ys: Vec<i64> = (bounds.min.1..bounds.max.1 + 1).rev().collect();
+
+        let rows: Vec<Vec<String>> = ys.iter().map(|&y| {
+            xs.iter().map(|&x| match label {
+                UlamLabel::Values  => self.get(Point(x, y)).map_or(".".to_string(), |value| value.to_string()),
+                UlamLabel::Squares => self.square_at(Point(x, y)).to_string(),
+            }).collect()
+        }).collect();
+
+        let width = rows.iter().flat_map(|row| row.iter().map(|cell| cell.len()))
+            .chain(xs.iter().map(|x| x.to_string().len()))
+            .max().unwrap_or(0);
+        let y_width = ys.iter().map(|y| y.to_string().len()).max().unwrap_or(0);
+
+        let mut lines = Vec::with_capacity(ys.len() + 2);
+        let header: String = xs.iter().map(|x| format!(" {:>width$} ", x, width = width)).collect();
+        lines.push(format!("{:y_width$}  {}", "", header, y_width = y_width));
+        lines.push(format!("{:y_width$} /{}", "", "-".repeat(xs.len() * (width + 2)), y_width = y_width));
+        for (&y, row) in ys.iter().zip(rows.iter()) {
+            let cells: String = xs.iter().zip(row.iter()).map(|(&x, cell)| {
+                if path.contains(&Point(x, y)) {
+                    format!("[{:>width$}]", cell, width = width)
+                } else {
+                    format!(" {:>width$} ", cell, width = width)
                 }
-            }
+            }).collect();
+            lines.push(format!("{:>y_width$} |{}", y, cells, y_width = y_width));
         }
 
-        v
+        lines.iter().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n")
     }
+}
 
-    fn neighbors_values(&self, coord: Coord) -> Vec<Option<&T>> {
-        self.neighbors(coord).iter().map(|coord| self.explored.get(coord)).collect()
+/// Where square `square` (counting from 2) sits in the spiral: which ring
+/// it's in, which side of the ring, and how many steps along that side.
+fn locate(square: u64) -> (u64, u64, u64) {
+    let ring = isqrt(square - 1).div_ceil(2);
+    let offset = square - (2 * ring - 1).pow(2) - 1;
+    (ring, offset / (2 * ring), offset % (2 * ring))
+}
+
+fn position_of(square: u64) -> Point {
+    assert!(square > 0, "squares are numbered from 1");
+    if square == 1 {
+        return Point::origin();
     }
 
-    fn iter(&self) -> UlamIterator {
-        UlamIterator::new()
+    let (ring, side, step) = locate(square);
+    let (k, t) = (ring as i64, step as i64);
+    match side {
+        0 => Point(k, -k + 1 + t),
+        1 => Point(k - 1 - t, k),
+        2 => Point(-k, k - 1 - t),
+        _ => Point(-k + 1 + t, -k),
     }
 }
 
+/// What `Ulam::render` shows in each cell.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UlamLabel {
+    Values, Squares
+}
 
-/// A direction that we're currently traversing in the grid.
-enum UlamIterDirection {
-    UP, DOWN, LEFT, RIGHT
+/// The cells from `start` to the origin, moving across first.
+fn path_to_origin(start: Point) -> impl Iterator<Item = Point> {
+    let Point(x, y) = start;
+    let across = (0..x.abs() + 1).map(move |dx| Point(x - dx * x.signum(), y));
+    let vertical = (1..y.abs() + 1).map(move |dy| Point(0, y - dy * y.signum()));
+    across.chain(vertical)
 }
 
-impl UlamIterDirection {
-    /// Returns the direction we'd prefer to be moving to next,
-    /// assuming that the cell in that direction isn't already taken.
-    /// For example, when moving to the right, we want to turn so we're
-    /// moving up — but only if the space above us is empty.
-    fn preferred_next_direction(&self) -> UlamIterDirection {
-        use self::UlamIterDirection::*;
-        match self {
-            &UP    => LEFT,
-            &LEFT  => DOWN,
-            &DOWN  => RIGHT,
-            &RIGHT => UP
-        }
+/// The largest integer whose square is at most `n`.
+fn isqrt(n: u64) -> u64 {
+    let mut root = (n as f64).sqrt() as u64;
+    // Floating point can be off by one either way for big numbers
+    while root * root > n {
+        root -= 1;
     }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
+}
 
-    /// Returns the (x, y) delta to move in the direction.
-    fn delta(&self) -> (i32, i32) {
-        use self::UlamIterDirection::*;
-        match self {
-            &UP    => ( 0,  1),
-            &DOWN  => ( 0, -1),
-            &LEFT  => (-1,  0),
-            &RIGHT => ( 1,  0)
-        }
-
+impl<T: Copy> Default for Ulam<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-/// An iterator that produces `(x, y)` tuples in the order that
-/// one traverses a Ulam spiral. Owns its own `Ulam` instance to
-/// keep track of which cells have been visited.
-struct UlamIterator {
-    ulam: Ulam<bool>,
-    current: Option<Coord>,
-    direction: Option<UlamIterDirection>,
+/// Which way a spiral winds as it leaves the origin. Counter-clockwise is
+/// the usual way, drawn above; a clockwise spiral is its mirror image,
+/// heading down from square 2 rather than up.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Winding {
+    CounterClockwise, Clockwise
+}
+
+/// The way to the next square along each side of a ring, counter-clockwise.
+const SIDE_DIRECTIONS: [Direction4; 4] = [Direction4::North, Direction4::West, Direction4::South, Direction4::East];
+
+/// A step in `dir` in the spiral's coordinates. `Direction4` points north
+/// towards negative `y`, but here `y` increases upward, so flip it over.
+fn step(dir: Direction4) -> Point {
+    let Point(x, y) = dir.delta();
+    Point(x, -y)
+}
+
+/// An iterator that produces `(x, y)` points in the order that one
+/// traverses a Ulam spiral. Rather than remembering where it's been, it
+/// counts which ring it's on, which side of that ring, and how far along
+/// the side, so it runs in constant memory and can skip ahead at once.
+pub struct UlamIterator {
+    square: u64,
+    position: Point,
+    ring: u64,
+    side: u64,
+    step: u64,
+    winding: Winding,
 }
 
 impl UlamIterator {
-    /// Create anew `UlamIterator` starting at the origin.
-    fn new() -> Self {
-        UlamIterator {
-            ulam: Ulam::new(),
-            current: None,
-            direction: None
+    /// Create a new counter-clockwise `UlamIterator` starting at the origin.
+    pub fn new() -> Self {
+        Self::at(1, Winding::CounterClockwise)
+    }
+
+    /// Create an iterator whose first point is that of square `square`,
+    /// or `None` for square 0, since squares are numbered from 1.
+    pub fn starting_at(square: u64, winding: Winding) -> Option<Self> {
+        if square == 0 {
+            None
+        } else {
+            Some(Self::at(square, winding))
         }
     }
+
+    fn at(square: u64, winding: Winding) -> Self {
+        let (ring, side, step) = if square > 1 { locate(square) } else { (0, 0, 0) };
+        UlamIterator { square, position: position_of(square), ring, side, step, winding }
+    }
+
+    /// The number of the square whose point comes next.
+    pub fn square(&self) -> u64 {
+        self.square
+    }
+}
+
+impl Default for UlamIterator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Iterator for UlamIterator {
-    type Item = Coord;
+    type Item = Point;
 
     /// Yields the next `(x, y)` coordinate pair in the Ulam spiral, following
     /// the direction of the spiral. This method never returns `None` (there can
     /// always be a next cell); be sure to constrain consumers appropriately.
-    fn next(&mut self) -> Option<Coord> {
-        match self.current {
-            // If we haven't yet started traversing the spiral,
-            // then the first element is `(0, 0)`.
-            None => {
-                let new_cell = (0, 0);
-                self.current = Some(new_cell);
-                self.ulam.set(new_cell, true);
-                Some(new_cell)
-            }
-            // If we've already started traversing the spiral...
-            Some((x, y)) => {
-                // ...then determine the next direction we should go (e.g. if we're
-                // moving to the right, then we want to go up next if we can).
-                // If we haven't started moving yet, we move to the right.
-                let preferred_next_direction = match self.direction {
-                    Some(ref dir) => dir.preferred_next_direction(),
-                    None          => UlamIterDirection::RIGHT
-                };
-                // Determine the next cell we'd like to move to.
-                let (dx, dy) = preferred_next_direction.delta();
-                let check_cell = (x + dx, y + dy);
-                match self.ulam.get(check_cell) {
-                    // If the cell we want to move to is taken, we simply
-                    // keep going our current direction.
-                    Some(_) => {
-                        let (dx, dy) = self.direction.as_ref().unwrap().delta();
-                        let next_cell = (x + dx, y + dy);
-                        self.current = Some(next_cell);
-                        self.ulam.set(next_cell, true);
-                        Some(next_cell)
-                    },
-                    // The cell we want to move to is free! Happy day!
-                    // Let's move to that one, and set our direction so we continue
-                    // moving in that direction in the next iteration.
-                    None => {
-                        self.current = Some(check_cell);
-                        self.ulam.set(check_cell, true);
-                        self.direction = Some(preferred_next_direction);
-                        Some(check_cell)
-                    }
+    fn next(&mut self) -> Option<Point> {
+        let current = self.position;
+        self.square += 1;
+
+        if self.ring == 0 {
+            // Leaving the origin for the first ring
+            self.ring = 1;
+            self.position = step(Direction4::East);
+        } else {
+            self.step += 1;
+            if self.step == 2 * self.ring {
+                self.step = 0;
+                self.side += 1;
+                if self.side == 4 {
+                    self.side = 0;
+                    self.ring += 1;
                 }
             }
+            // Each ring starts one step to the right of where the last one ended
+            let dir = if self.side == 0 && self.step == 0 { Direction4::East } else { SIDE_DIRECTIONS[self.side as usize] };
+            self.position = self.position + step(dir);
         }
+
+        Some(match self.winding {
+            Winding::CounterClockwise => current,
+            Winding::Clockwise        => Point(current.0, -current.1),
+        })
+    }
+
+    /// Skips straight to the `n`th point from here, without visiting the ones in between.
+    fn nth(&mut self, n: usize) -> Option<Point> {
+        *self = Self::at(self.square + n as u64, self.winding);
+        self.next()
     }
 }
 
 #[test]
 fn test_grid_set_get() {
     let mut g: Ulam<u32> = Ulam::new();
-    g.set((0, 0), 42);
-    assert_eq!(g.get((0, 0)), Some(&42));
-    assert_eq!(g.get((0, 1)), None);
+    g.set(Point(0, 0), 42);
+    assert_eq!(g.get(Point(0, 0)), Some(&42));
+    assert_eq!(g.get(Point(0, 1)), None);
 }
 
 #[test]
 fn test_grid_neighbors_values() {
     let mut g: Ulam<u32> = Ulam::new();
-    assert_eq!(g.neighbors_values((0, 0)), vec![None, None, None, None, None, None, None, None]);
-
-    g.set((-1,  1), 1);
-    g.set(( 0,  1), 2);
-    g.set(( 1,  1), 3);
-    g.set((-1,  0), 4);
-    g.set(( 0,  0), 5);
-    g.set(( 1,  0), 6);
-    g.set((-1, -1), 7);
-    g.set(( 0, -1), 8);
-    g.set(( 1, -1), 9);
-
-    let total: u32 = g.neighbors_values((0, 0))
+    assert_eq!(g.neighbors_values(Point(0, 0)), vec![None, None, None, None, None, None, None, None]);
+
+    g.set(Point(-1, 1), 1);
+    g.set(Point( 0, 1), 2);
+    g.set(Point( 1, 1), 3);
+    g.set(Point(-1, 0), 4);
+    g.set(Point( 0, 0), 5);
+    g.set(Point( 1, 0), 6);
+    g.set(Point(-1, -1), 7);
+    g.set(Point( 0, -1), 8);
+    g.set(Point( 1, -1), 9);
+
+    let total: u32 = g.neighbors_values(Point(0, 0))
         .iter()
         .map(|opt| opt.unwrap())
         .sum();
@@ -204,9 +328,9 @@ fn test_grid_neighbors_values() {
 #[test]
 fn test_grid_iteration() {
     let g: Ulam<u32> = Ulam::new();
-    let mut v: Vec<Coord> = Vec::new();
+    let mut v: Vec<(i64, i64)> = Vec::new();
     for cell in g.iter().take(26) {
-        v.push(cell);
+        v.push(cell.into());
     }
 
     assert_eq!(v, vec![
@@ -216,16 +340,51 @@ fn test_grid_iteration() {
     ]);
 }
 
-fn distance_for_square(n: u64) -> i32 {
+#[test]
+fn test_step() {
+    assert_eq!(step(Direction4::North), Point(0, 1));
+    assert_eq!(step(Direction4::East), Point(1, 0));
+    assert_eq!(Ulam::<u64>::new().position_of(3), step(Direction4::East) + step(Direction4::North));
+}
+
+#[test]
+fn test_iterator_windings() {
+    let clockwise: Vec<Point> = UlamIterator::starting_at(1, Winding::Clockwise).unwrap().take(4).collect();
+    assert_eq!(clockwise, vec![Point(0, 0), Point(1, 0), Point(1, -1), Point(0, -1)]);
+
+    let mut iter = UlamIterator::starting_at(10, Winding::CounterClockwise).unwrap();
+    assert_eq!(iter.next(), Some(Point(2, -1)));
+    assert_eq!(iter.square(), 11);
+    assert_eq!(iter.nth(14), Some(Point(2, -2)));
+    assert_eq!(iter.next(), Some(Point(3, -2)));
+
+    let mut iter = UlamIterator::starting_at(1, Winding::Clockwise).unwrap();
+    assert_eq!(iter.nth(289_325), Some(Point(-150, -269)));
+    assert_eq!(UlamIterator::new().nth(289_325), Some(Point(-150, 269)));
+
+    assert!(UlamIterator::starting_at(0, Winding::CounterClockwise).is_none());
+    assert!(UlamIterator::starting_at(0, Winding::Clockwise).is_none());
+}
+
+fn distance_for_square(n: u64) -> i64 {
     let g: Ulam<u64> = Ulam::new();
-    for (i, cell) in g.iter().enumerate() {
-        if (i as u64 + 1) == n {
-            let (x, y) = cell;
-            return x.abs() + y.abs();
-        }
+    g.position_of(n).manhattan(Point::origin())
+}
+
+#[test]
+fn test_position_of() {
+    let g: Ulam<u64> = Ulam::new();
+    for (i, cell) in g.iter().take(2000).enumerate() {
+        let square = i as u64 + 1;
+        assert_eq!(g.position_of(square), cell);
+        assert_eq!(g.square_at(cell), square);
     }
 
-    0
+    assert_eq!(g.position_of(49), Point(3, -3));
+    assert_eq!(g.position_of(50), Point(4, -3));
+    let huge = 1_000_000_000_000_000;
+    assert_eq!(g.square_at(g.position_of(huge)), huge);
+    assert_eq!(isqrt(huge), 31_622_776);
 }
 
 #[test]
@@ -236,44 +395,144 @@ fn test_distance_for_square() {
     assert_eq!(distance_for_square(1024), 31);
 }
 
+#[test]
+fn test_render() {
+    let g: Ulam<u64> = Ulam::new();
+    let bounds = Bounds { min: Point(-1, -1), max: Point(2, 1) };
+    assert_eq!(g.render(bounds, UlamLabel::Squares, Some(Point(2, 1))), [
+        "     -1   0   1   2",
+        "   /----------------",
+        " 1 |  5 [ 4][ 3][12]",
+        " 0 |  6 [ 1]  2  11",
+        "-1 |  7   8   9  10",
+    ].join("\n"));
+
+    let mut g: Ulam<u64> = Ulam::new();
+    g.set(Point(0, 0), 1);
+    g.set(Point(1, 0), 1);
+    g.set(Point(1, 1), 2);
+    assert_eq!(g.render(Bounds { min: Point(0, 0), max: Point(1, 1) }, UlamLabel::Values, None), [
+        "    0  1",
+        "  /------",
+        "1 | .  2",
+        "0 | 1  1",
+    ].join("\n"));
+}
+
 #[test]
 fn test_day03() {
-    // Part 1 - takes some time to calculate in debug builds
-    // assert_eq!(distance_for_square(289326), 419);
+    // Part 1
+    assert_eq!(distance_for_square(289326), 419);
 
     // Part 2
     assert_eq!(part2(289326), 295229);
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/03.txt").expect("expected input 03.txt");
-    let num: u64 = input.parse().expect("couldn't parse input as a number");
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+
+    type Input = u64;
+    type Part1 = i64;
+    type Part2 = u64;
+
+    fn parse(&self, input: &str) -> Result<u64> {
+        match input.trim().parse() {
+            Ok(0)      => Err(AdventError::parse("squares are numbered from 1")),
+            Ok(square) => Ok(square),
+            Err(_)     => Err(AdventError::parse("couldn't parse input as a number")),
+        }
+    }
+
+    fn part1(&self, &num: &u64) -> Result<i64> {
+        Ok(distance_for_square(num))
+    }
 
-    println!("Part 1: {}", distance_for_square(num));
-    println!("Part 2: {}", part2(num));
+    fn part2(&self, &num: &u64) -> Result<u64> {
+        Ok(part2(num))
+    }
 }
 
+/// The first value written to the stress test spiral that's larger than `check`.
 fn part2(check: u64) -> u64 {
-    //  Create a new Ulam spiral
-    let mut g: Ulam<u64> = Ulam::new();
-    // Prime the first cell
-    g.set((0, 0), 1);
-
-    // Skipping the first cell ...
-    for cell in g.iter().skip(1) {
-        let sum = {
-            // ... find all the values of the neighbors ...
-            let values = g.neighbors_values(cell);
-            // ... and sum the ones that exist (the ones that don't default to 0)
-            values.iter().map(|opt| opt.unwrap_or(&0)).sum()
+    // Each square gets the sum of the (up to eight) squares around it that are already set
+    let mut fill = SpiralFill::new(1, Neighborhood::Eight, |values: &[u64]| values.iter().sum());
+    let (_, _, value) = fill.find(|&(_, _, value)| value > check).expect("the spiral never ends");
+    value
+}
+
+/// Which of the cells around a square count as its neighbours.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Neighborhood {
+    /// Only the cells directly above, below, left and right.
+    Four,
+    /// The diagonals as well.
+    Eight,
+}
+
+/// Fills a Ulam spiral one square at a time, in order: the origin gets
+/// `seed`, and every later square gets the result of `fold` on the values
+/// of its neighbours that have already been filled. Yields each square's
+/// number, coordinate and value as it goes.
+///
+/// Summing eight neighbours from a seed of 1 gives OEIS A141481, the
+/// stress test from Part 2.
+pub struct SpiralFill<T: Copy, F> {
+    ulam: Ulam<T>,
+    squares: UlamIterator,
+    neighborhood: Neighborhood,
+    seed: T,
+    fold: F,
+}
+
+impl<T: Copy, F> SpiralFill<T, F> where F: FnMut(&[T]) -> T {
+    pub fn new(seed: T, neighborhood: Neighborhood, fold: F) -> Self {
+        SpiralFill { ulam: Ulam::new(), squares: UlamIterator::new(), neighborhood, seed, fold }
+    }
+
+    /// The spiral as filled so far, for instance to `render` it.
+    pub fn ulam(&self) -> &Ulam<T> {
+        &self.ulam
+    }
+}
+
+impl<T: Copy, F> Iterator for SpiralFill<T, F> where F: FnMut(&[T]) -> T {
+    type Item = (u64, Point, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let square = self.squares.square();
+        let cell = self.squares.next()?;
+        let value = if square == 1 {
+            self.seed
+        } else {
+            let values: Vec<T> = match self.neighborhood {
+                Neighborhood::Four  => cell.neighbors4().iter().filter_map(|&n| self.ulam.get(n).cloned()).collect(),
+                Neighborhood::Eight => cell.neighbors8().iter().filter_map(|&n| self.ulam.get(n).cloned()).collect(),
+            };
+            (self.fold)(&values)
         };
-        // Set the sum for the current cell
-        g.set(cell, sum);
-        // Check to see if we found our magic number
-        if sum > check {
-            return sum
-        }
+        self.ulam.set(cell, value);
+        Some((square, cell, value))
     }
+}
+
+#[test]
+fn test_spiral_fill() {
+    let values = |fill: &mut dyn Iterator<Item = (u64, Point, u64)>, n| fill.take(n).map(|(_, _, v)| v).collect::<Vec<_>>();
+
+    let mut sums = SpiralFill::new(1, Neighborhood::Eight, |values: &[u64]| values.iter().sum());
+    assert_eq!(values(&mut sums, 23), vec![
+        1, 1, 2, 4, 5, 10, 11, 23, 25, 26, 54, 57, 59, 122, 133, 142, 147, 304, 330, 351, 362, 747, 806
+    ]);
+    assert_eq!(sums.ulam().get(Point(0, 1)), Some(&4));
+
+    let mut sums4 = SpiralFill::new(1, Neighborhood::Four, |values: &[u64]| values.iter().sum());
+    assert_eq!(values(&mut sums4, 10), vec![1, 1, 1, 2, 2, 3, 3, 4, 5, 5]);
+
+    let mut counts = SpiralFill::new(0, Neighborhood::Eight, |values: &[u64]| values.len() as u64);
+    assert_eq!(values(&mut counts, 9), vec![0, 1, 2, 3, 2, 3, 2, 4, 3]);
 
-    0
+    let mut products = SpiralFill::new(2, Neighborhood::Four, |values: &[u64]| values.iter().product());
+    assert_eq!(products.nth(3), Some((4, Point(0, 1), 4)));
 }
@@ -0,0 +1,88 @@
+#[cfg(test)]
+use common;
+use error::Result;
+use solution::Solution;
+use std::collections::HashSet;
+
+pub struct Day04;
+
+/// A passphrase is a list of lowercase words separated by spaces.
+pub type Passphrase = Vec<String>;
+
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+
+    type Input = Vec<Passphrase>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<Passphrase>> {
+        Ok(input.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split_whitespace().map(|word| word.to_string()).collect())
+            .collect())
+    }
+
+    fn part1(&self, passphrases: &Vec<Passphrase>) -> Result<usize> {
+        Ok(passphrases.iter().filter(|p| !contains_duplicate_words(p)).count())
+    }
+
+    fn part2(&self, passphrases: &Vec<Passphrase>) -> Result<usize> {
+        Ok(passphrases.iter().filter(|p| !contains_anagrams(p)).count())
+    }
+
+    fn describe_part1(&self, count: &usize) -> String {
+        format!("{} passphrases have no duplicate words", count)
+    }
+
+    fn describe_part2(&self, count: &usize) -> String {
+        format!("{} passphrases have no anagrams", count)
+    }
+}
+
+fn contains_duplicate_words(passphrase: &[String]) -> bool {
+    contains_duplicates(passphrase.iter().cloned())
+}
+
+/// Two words are anagrams if they have the same letters once sorted.
+fn contains_anagrams(passphrase: &[String]) -> bool {
+    contains_duplicates(passphrase.iter().map(|word| {
+        let mut letters: Vec<char> = word.chars().collect();
+        letters.sort();
+        letters.into_iter().collect::<String>()
+    }))
+}
+
+fn contains_duplicates<I: Iterator<Item = String>>(words: I) -> bool {
+    let mut seen = HashSet::new();
+    for word in words {
+        if !seen.insert(word) {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[test]
+fn test_day04() {
+    let passphrases = Day04.parse("aa bb cc dd ee\naa bb cc dd aa\naa bb cc dd aaa\n").unwrap();
+    assert_eq!(passphrases.len(), 3);
+    assert_eq!(Day04.part1(&passphrases).unwrap(), 2);
+    let passphrases = Day04.parse("abcde fghij\nabcde xyz ecdab\na ab abc abd abf abj\niiii oiii ooii oooi oooo\noiii ioii iioi iiio").unwrap();
+    assert_eq!(Day04.part2(&passphrases).unwrap(), 3);
+
+    let words = |s: &str| s.split_whitespace().map(|w| w.to_string()).collect::<Vec<_>>();
+    assert!(!contains_anagrams(&words("abcde fghij")));
+    assert!(contains_anagrams(&words("abcde xyz ecdab")));
+    assert!(!contains_anagrams(&words("a ab abc abd abf abj")));
+    assert!(!contains_anagrams(&words("iiii oiii ooii oooi oooo")));
+    assert!(contains_anagrams(&words("oiii ioii iioi iiio")));
+}
+
+#[test]
+fn test_day04_input() {
+    let passphrases = Day04.parse(&common::get_input("./inputs/04.txt").expect("expected input 04.txt")).unwrap();
+    assert_eq!(Day04.part1(&passphrases).unwrap(), 386);
+    assert_eq!(Day04.part2(&passphrases).unwrap(), 208);
+}
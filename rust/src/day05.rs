@@ -0,0 +1,92 @@
+#[cfg(test)]
+use common;
+use error::{self, AdventError, Result};
+use solution::Solution;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+
+    type Input = Vec<i32>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>> {
+        error::parse_lines(input.trim(), |line| {
+            line.trim().parse().map_err(|_| AdventError::parse(format!("couldn't parse offset '{}'", line)))
+        })
+    }
+
+    fn part1(&self, offsets: &Vec<i32>) -> Result<usize> {
+        Ok(JumpMaze::new(offsets).traverse(|n| n + 1))
+    }
+
+    fn part2(&self, offsets: &Vec<i32>) -> Result<usize> {
+        Ok(JumpMaze::new(offsets).traverse(|n| if n >= 3 { n - 1 } else { n + 1 }))
+    }
+
+    fn describe_part1(&self, steps: &usize) -> String {
+        format!("Reached the end in {} steps", steps)
+    }
+
+    fn describe_part2(&self, steps: &usize) -> String {
+        format!("Reached the end in {} steps", steps)
+    }
+}
+
+/// A list of jump offsets. Each jump moves by the current offset, and
+/// then the offset that was just used is changed for next time.
+pub struct JumpMaze {
+    offsets: Vec<i32>,
+    current: i64,
+    steps: usize,
+}
+
+impl JumpMaze {
+    pub fn new(offsets: &[i32]) -> Self {
+        JumpMaze { offsets: offsets.to_vec(), current: 0, steps: 0 }
+    }
+
+    /// Whether we've jumped outside the list, in either direction.
+    pub fn is_exited(&self) -> bool {
+        self.current < 0 || self.current >= self.offsets.len() as i64
+    }
+
+    /// Makes a single jump, using `modifier` to update the offset we jumped from.
+    pub fn jump<F: Fn(i32) -> i32>(&mut self, modifier: F) {
+        let idx = self.current as usize;
+        let offset = self.offsets[idx];
+        self.offsets[idx] = modifier(offset);
+        self.current += offset as i64;
+        self.steps += 1;
+    }
+
+    /// Jumps until we've exited the maze, returning the number of steps it took.
+    pub fn traverse<F: Fn(i32) -> i32>(&mut self, modifier: F) -> usize {
+        while !self.is_exited() {
+            self.jump(&modifier);
+        }
+
+        self.steps
+    }
+}
+
+#[test]
+fn test_jump_maze() {
+    let offsets = Day05.parse("0\n3\n0\n1\n-3\n").unwrap();
+    assert_eq!(Day05.part1(&offsets).unwrap(), 5);
+    assert_eq!(Day05.part2(&offsets).unwrap(), 10);
+
+    let mut maze = JumpMaze::new(&offsets);
+    maze.jump(|n| n + 1);
+    assert_eq!(maze.offsets, vec![1, 3, 0, 1, -3]);
+    assert_eq!(Day05.parse("0\nx").unwrap_err().to_string(), "line 2: couldn't parse offset 'x'");
+}
+
+#[test]
+fn test_day05_input() {
+    let offsets = Day05.parse(&common::get_input("./inputs/05.txt").expect("expected input 05.txt")).unwrap();
+    assert_eq!(Day05.part1(&offsets).unwrap(), 318883);
+    assert_eq!(Day05.part2(&offsets).unwrap(), 23948711);
+}
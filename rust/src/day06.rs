@@ -0,0 +1,101 @@
+#[cfg(test)]
+use common;
+use cycle::{self, Cycle};
+use error::{AdventError, Result};
+use solution::Solution;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+
+    type Input = Vec<u32>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>> {
+        let input = input.trim();
+        input.split_whitespace().map(|bank| {
+            bank.parse().map_err(|_| AdventError::parse(format!("couldn't parse bank size '{}'", bank)).at(input, bank))
+        }).collect()
+    }
+
+    fn part1(&self, banks: &Vec<u32>) -> Result<usize> {
+        let cycle = reallocate(banks);
+        Ok(cycle.prefix + cycle.length)
+    }
+
+    fn part2(&self, banks: &Vec<u32>) -> Result<usize> {
+        Ok(reallocate(banks).length)
+    }
+
+    fn describe_part1(&self, steps: &usize) -> String {
+        format!("Steps: {}", steps)
+    }
+
+    fn describe_part2(&self, loop_size: &usize) -> String {
+        format!("Loop size: {}", loop_size)
+    }
+}
+
+/// Memory banks that can be rebalanced by spreading the blocks
+/// of the fullest bank over the others.
+pub struct MemoryAllocator {
+    banks: Vec<u32>,
+}
+
+impl MemoryAllocator {
+    pub fn new(banks: &[u32]) -> Self {
+        MemoryAllocator { banks: banks.to_vec() }
+    }
+
+    pub fn banks(&self) -> &[u32] {
+        &self.banks
+    }
+
+    /// Empties the fullest bank (the first one, on ties) and hands its
+    /// blocks out one at a time to the banks that follow it, wrapping around.
+    pub fn balance(&mut self) {
+        if self.banks.is_empty() {
+            return;
+        }
+
+        let max = *self.banks.iter().max().unwrap();
+        let mut index = self.banks.iter().position(|&bank| bank == max).unwrap();
+        self.banks[index] = 0;
+        for _ in 0..max {
+            index = (index + 1) % self.banks.len();
+            self.banks[index] += 1;
+        }
+    }
+}
+
+/// Finds the loop that repeatedly balancing the banks falls into. The
+/// first repeated configuration is seen after `prefix + length` balances.
+fn reallocate(banks: &[u32]) -> Cycle {
+    cycle::hashed(banks.to_vec(), |banks| {
+        let mut allocator = MemoryAllocator::new(banks);
+        allocator.balance();
+        allocator.banks().to_vec()
+    })
+}
+
+#[test]
+fn test_memory_allocator() {
+    let mut allocator = MemoryAllocator::new(&[0, 2, 7, 0]);
+    allocator.balance();
+    assert_eq!(allocator.banks(), &[2, 4, 1, 2]);
+    allocator.balance();
+    assert_eq!(allocator.banks(), &[3, 1, 2, 3]);
+
+    let banks = Day06.parse("0\t2\t7\t0\n").unwrap();
+    assert_eq!(Day06.part1(&banks).unwrap(), 5);
+    assert_eq!(Day06.part2(&banks).unwrap(), 4);
+}
+
+#[test]
+fn test_day06_input() {
+    let banks = Day06.parse(&common::get_input("./inputs/06.txt").expect("expected input 06.txt")).unwrap();
+    assert_eq!(Day06.part1(&banks).unwrap(), 11137);
+    assert_eq!(Day06.part2(&banks).unwrap(), 1037);
+}
@@ -0,0 +1,241 @@
+use error::{self, AdventError, Result};
+use solution::Solution;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+pub struct Day07;
+
+/// A program in the tower, with the names of the programs it's holding up.
+#[derive(Debug, PartialEq)]
+pub struct Program {
+    name: String,
+    weight: u32,
+    children: Vec<String>,
+}
+
+impl Program {
+    /// Parses a line like `fwft (72) -> ktlj, cntj, xhth`.
+    fn parse(line: &str) -> Result<Self> {
+        let (program, children) = match line.find(" -> ") {
+            Some(idx) => (&line[..idx], line[idx+4..].split(", ").map(|c| c.trim().to_string()).collect()),
+            None      => (line, vec![]),
+        };
+
+        let mut parts = program.split_whitespace();
+        let name = error::next_token(&mut parts, program, "a program name")?.to_string();
+        let weight_str = error::next_token(&mut parts, program, "a weight")?;
+        let weight = weight_str.trim_start_matches('(').trim_end_matches(')').parse().map_err(|_| {
+            AdventError::parse(format!("couldn't parse weight '{}'", weight_str)).at(line, weight_str)
+        })?;
+
+        Ok(Program { name, weight, children })
+    }
+}
+
+#[test]
+fn test_parse_program() {
+    assert_eq!(Program::parse("pbga (66)").unwrap(), Program { name: "pbga".to_string(), weight: 66, children: vec![] });
+    assert_eq!(Program::parse("fwft (72) -> ktlj, cntj").unwrap(), Program {
+        name: "fwft".to_string(),
+        weight: 72,
+        children: vec!["ktlj".to_string(), "cntj".to_string()],
+    });
+    assert_eq!(Program::parse("fwft (x) -> ktlj").unwrap_err().to_string(), "column 6: couldn't parse weight '(x)'");
+}
+
+/// A tower of programs, each balancing the programs it holds on its own disc.
+#[derive(Debug)]
+pub struct Tower {
+    programs: HashMap<String, Program>,
+    root: String,
+}
+
+/// The one program whose weight throws the tower off balance.
+#[derive(Debug, PartialEq)]
+pub struct Unbalanced {
+    /// The programs from the bottom of the tower to the unbalanced one.
+    pub path: Vec<String>,
+    pub weight: u32,
+    pub corrected_weight: u32,
+}
+
+impl fmt::Display for Unbalanced {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.corrected_weight)
+    }
+}
+
+impl Tower {
+    /// Builds the tower, making sure every program it mentions exists,
+    /// that exactly one program sits at the bottom, and that the rest form
+    /// a tree above it: each held up by just one program, with no loops.
+    pub fn new(programs: Vec<Program>) -> Result<Self> {
+        let programs: HashMap<String, Program> = programs.into_iter().map(|p| (p.name.clone(), p)).collect();
+        let mut held: HashMap<&str, &str> = HashMap::new();
+        let mut names: Vec<&String> = programs.keys().collect();
+        names.sort();
+        for program in names.iter().map(|&name| &programs[name]) {
+            for child in &program.children {
+                if !programs.contains_key(child) {
+                    return Err(AdventError::parse(format!("{} holds up {}, which isn't in the tower", program.name, child)));
+                }
+                if let Some(other) = held.insert(child, &program.name) {
+                    return Err(AdventError::parse(format!("{} is held up by both {} and {}", child, other, program.name)));
+                }
+            }
+        }
+
+        let mut roots: Vec<&String> = programs.keys().filter(|name| !held.contains_key(name.as_str())).collect();
+        roots.sort();
+        let root = match roots.len() {
+            1 => roots[0].clone(),
+            0 => return Err(AdventError::parse("every program is held up by another; the tower has no bottom")),
+            _ => return Err(AdventError::parse(format!("the tower has more than one bottom program: {:?}", roots))),
+        };
+
+        // With one holder each, any program out of reach of the bottom
+        // must be in a loop of programs holding each other up
+        let mut reached = HashSet::new();
+        let mut stack = vec![root.as_str()];
+        while let Some(name) = stack.pop() {
+            reached.insert(name);
+            stack.extend(programs[name].children.iter().map(|child| child.as_str()));
+        }
+        let mut looped: Vec<&String> = programs.keys().filter(|name| !reached.contains(name.as_str())).collect();
+        if !looped.is_empty() {
+            looped.sort();
+            return Err(AdventError::parse(format!("these programs hold each other up in a loop: {:?}", looped)));
+        }
+
+        Ok(Tower { programs, root })
+    }
+
+    /// The program at the bottom of the tower.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// The weight of a program plus everything it's holding up.
+    pub fn total_weight(&self, name: &str) -> u32 {
+        let program = &self.programs[name];
+        program.weight + program.children.iter().map(|child| self.total_weight(child)).sum::<u32>()
+    }
+
+    /// Follows the odd one out up the tower until reaching a program whose
+    /// own disc is balanced; that program's weight is the one that's wrong.
+    pub fn find_unbalanced(&self) -> Result<Unbalanced> {
+        let mut path = vec![self.root.clone()];
+        let mut target = None;
+
+        loop {
+            let program = &self.programs[path.last().unwrap()];
+            let weights: Vec<u32> = program.children.iter().map(|child| self.total_weight(child)).collect();
+
+            match odd_one_out(&weights)? {
+                Some((idx, expected)) => {
+                    target = Some(expected);
+                    path.push(program.children[idx].clone());
+                },
+                None => {
+                    let expected = target.ok_or_else(|| AdventError::solve("the tower is already balanced"))?;
+                    let actual = self.total_weight(&program.name);
+                    let corrected_weight = (program.weight + expected).checked_sub(actual)
+                        .ok_or_else(|| AdventError::solve(format!("{} can't be made light enough", program.name)))?;
+                    return Ok(Unbalanced { path, weight: program.weight, corrected_weight });
+                },
+            }
+        }
+    }
+}
+
+/// Finds the index of the single weight that differs from the rest,
+/// along with the weight everything else agrees on.
+fn odd_one_out(weights: &[u32]) -> Result<Option<(usize, u32)>> {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for &weight in weights {
+        *counts.entry(weight).or_insert(0) += 1;
+    }
+
+    match counts.len() {
+        0 | 1 => Ok(None),
+        2 => {
+            let odd = counts.iter().find(|&(_, &count)| count == 1);
+            let common = counts.iter().find(|&(_, &count)| count > 1);
+            match (odd, common) {
+                (Some((&odd, _)), Some((&common, _))) => {
+                    Ok(Some((weights.iter().position(|&w| w == odd).unwrap(), common)))
+                },
+                _ => Err(AdventError::solve(format!("can't tell which of the weights {:?} is wrong", weights))),
+            }
+        },
+        _ => Err(AdventError::solve(format!("more than one of the weights {:?} is wrong", weights))),
+    }
+}
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+
+    type Input = Tower;
+    type Part1 = String;
+    type Part2 = Unbalanced;
+
+    fn parse(&self, input: &str) -> Result<Tower> {
+        let programs = error::parse_lines(input.trim(), Program::parse)?;
+        Tower::new(programs)
+    }
+
+    fn part1(&self, tower: &Tower) -> Result<String> {
+        Ok(tower.root().to_string())
+    }
+
+    fn part2(&self, tower: &Tower) -> Result<Unbalanced> {
+        tower.find_unbalanced()
+    }
+
+    fn describe_part1(&self, root: &String) -> String {
+        format!("{} is at the bottom of the tower", root)
+    }
+
+    fn describe_part2(&self, unbalanced: &Unbalanced) -> String {
+        format!("{} weighs {} but should weigh {} (path: {})",
+                unbalanced.path.last().unwrap(), unbalanced.weight, unbalanced.corrected_weight,
+                unbalanced.path.join(" -> "))
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+pbga (66)
+xhth (57)
+ebii (61)
+havc (66)
+ktlj (57)
+fwft (72) -> ktlj, cntj, xhth
+qoyq (66)
+padx (45) -> pbga, havc, qoyq
+tknk (41) -> ugml, padx, fwft
+jptl (61)
+ugml (68) -> gyxo, ebii, jptl
+gyxo (61)
+cntj (57)";
+
+#[test]
+fn test_tower() {
+    let tower = Day07.parse(EXAMPLE).unwrap();
+    assert_eq!(tower.root(), "tknk");
+    assert_eq!(tower.total_weight("ugml"), 251);
+    assert_eq!(tower.total_weight("padx"), 243);
+    assert_eq!(tower.find_unbalanced().unwrap(), Unbalanced {
+        path: vec!["tknk".to_string(), "ugml".to_string()],
+        weight: 68,
+        corrected_weight: 60,
+    });
+
+    assert!(Day07.parse("pbga (66)\nxhth (57)").is_err());
+    assert!(Day07.parse("tknk (41) -> nope").is_err());
+
+    let err = Day07.parse("a (1) -> b\nb (1) -> c\nc (1) -> b").unwrap_err();
+    assert_eq!(err.to_string(), "b is held up by both a and c");
+    let err = Day07.parse("a (1) -> b\nb (1)\nc (1) -> d\nd (1) -> c").unwrap_err();
+    assert_eq!(err.to_string(), "these programs hold each other up in a loop: [\"c\", \"d\"]");
+}
@@ -1,191 +1,1035 @@
 extern crate num;
 
-use common;
-use std::str::FromStr;
+use error::{self, AdventError, Result};
+use solution::Solution;
+use std::fmt;
 use std::collections;
-use std::collections::HashMap;
-use self::num::Num;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::marker::PhantomData;
+use self::num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Num};
 
-#[derive(Debug, PartialEq)]
+/// What registers can hold: whole numbers that can be compared, and whose
+/// arithmetic says when it overflows. `BigInt` never does, but fixed-width
+/// types like `i64` can.
+pub trait Value: Num + Default + Clone + Ord + fmt::Display + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + 'static {}
+
+impl<T> Value for T
+    where T: Num + Default + Clone + Ord + fmt::Display + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + 'static {}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Operation {
-    INC, DEC
+    Inc, Dec, Set, Mul
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Comparator {
-    EQ, NEQ, LT, LTE, GT, GTE
+    Eq, Neq, Lt, Lte, Gt, Gte
+}
+
+impl Comparator {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "==" => Some(Comparator::Eq),
+            "!=" => Some(Comparator::Neq),
+            "<"  => Some(Comparator::Lt),
+            "<=" => Some(Comparator::Lte),
+            ">"  => Some(Comparator::Gt),
+            ">=" => Some(Comparator::Gte),
+            _    => None,
+        }
+    }
+
+    fn holds<T: PartialOrd>(&self, left: &T, right: &T) -> bool {
+        match *self {
+            Comparator::Eq  => left == right,
+            Comparator::Neq => left != right,
+            Comparator::Lt  => left <  right,
+            Comparator::Lte => left <= right,
+            Comparator::Gt  => left >  right,
+            Comparator::Gte => left >= right
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum BinaryOp {
+    Add, Sub, Mul, Div, Rem
+}
+
+impl BinaryOp {
+    fn symbol(&self) -> &'static str {
+        match *self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+        }
+    }
+
+    /// Applies the operation, failing rather than overflowing or
+    /// dividing by zero.
+    fn apply<T: Value>(&self, left: &T, right: &T) -> Result<T> {
+        let result = match *self {
+            BinaryOp::Add => left.checked_add(right),
+            BinaryOp::Sub => left.checked_sub(right),
+            BinaryOp::Mul => left.checked_mul(right),
+            BinaryOp::Div | BinaryOp::Rem if right.is_zero() => {
+                return Err(AdventError::solve("division by zero"))
+            },
+            BinaryOp::Div => left.checked_div(right),
+            // The remainder can only overflow when the quotient does
+            BinaryOp::Rem => left.checked_div(right).map(|_| left.clone() % right.clone()),
+        };
+        result.ok_or_else(|| AdventError::solve(format!("overflow computing {} {} {}", left, self.symbol(), right)))
+    }
 }
 
 #[derive(Debug)]
-struct Program<T: Num + Default + Copy + PartialOrd> {
+pub struct Program<T: Value> {
     instructions: Vec<Instruction<T>>
 }
 
-impl<T: Num + Default + Copy + PartialOrd> Program<T> {
-    fn parse(input: &str) -> Self {
-        let instructions = input.lines().map(|line| Instruction::parse(line)).collect();
-        Program::new(instructions)
+impl<T: Value> Program<T> {
+    pub fn parse(input: &str) -> Result<Self> {
+        let instructions = error::parse_lines(input, Instruction::parse)?;
+        Ok(Program::new(instructions))
     }
 
     fn new(instructions: Vec<Instruction<T>>) -> Self {
         Program { instructions }
     }
+
+    pub fn compile(&self) -> Compiled<T> {
+        let mut names = Names::default();
+        let instructions = self.instructions.iter().map(|instruction| CompiledInstruction {
+            register: names.intern(&instruction.register),
+            operation: instruction.operation,
+            amount: instruction.amount.compile(&mut names),
+            condition: instruction.condition.compile(&mut names),
+        }).collect();
+        Compiled { names: names.names, instructions }
+    }
+}
+
+/// A piece of an instruction: a register name or keyword, a number, or
+/// an operator. Each holds the slice of the line it was read from, so
+/// errors can point at it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Token<'a> {
+    Word(&'a str),
+    Number(&'a str),
+    Symbol(&'a str),
+}
+
+impl<'a> Token<'a> {
+    fn text(&self) -> &'a str {
+        match *self {
+            Token::Word(text) | Token::Number(text) | Token::Symbol(text) => text
+        }
+    }
+}
+
+/// Two-character symbols come first, so `<=` isn't read as `<` then `=`.
+const SYMBOLS: [&str; 13] = ["==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "(", ")"];
+
+fn tokenize(line: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = vec![];
+    let mut rest = line.trim_start();
+
+    while let Some(first) = rest.chars().next() {
+        let (token, after) = if first.is_alphabetic() || first == '_' {
+            let (word, after) = rest.split_at(rest.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(rest.len()));
+            (Token::Word(word), after)
+        } else if first.is_ascii_digit() {
+            let (number, after) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len()));
+            (Token::Number(number), after)
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            let (symbol, after) = rest.split_at(symbol.len());
+            (Token::Symbol(symbol), after)
+        } else {
+            return Err(AdventError::parse(format!("unexpected character '{}'", first)).at(line, rest))
+        };
+        tokens.push(token);
+        rest = after.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+#[test]
+fn test_tokenize() {
+    use self::Token::*;
+
+    assert_eq!(tokenize("b inc -5 if (a_1+2)>=c").unwrap(), vec![
+        Word("b"), Word("inc"), Symbol("-"), Number("5"), Word("if"),
+        Symbol("("), Word("a_1"), Symbol("+"), Number("2"), Symbol(")"), Symbol(">="), Word("c")
+    ]);
+    assert_eq!(tokenize("  ").unwrap(), vec![]);
+    let err = tokenize("tux inc 1 if tux =< 42").unwrap_err();
+    assert_eq!(err.to_string(), "column 18: unexpected character '='");
+}
+
+/// Reads instructions from a line of tokens by recursive descent, one
+/// method per level of precedence, from `condition` (loosest) down
+/// to `atom` (tightest).
+///
+/// Any word can name a register, even `if`, `and`, `or`, `not` or an
+/// operation: whether a word is a keyword depends on where it appears.
+struct Parser<'a> {
+    line: &'a str,
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(line: &'a str) -> Result<Self> {
+        Ok(Parser { line, tokens: tokenize(line)?, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).cloned()
+    }
+
+    /// Moves past the next token if its text is `text`.
+    fn eat(&mut self, text: &str) -> bool {
+        let found = self.peek().is_some_and(|token| token.text() == text);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Complains that we expected `what` instead of the next token,
+    /// or instead of the end of the line.
+    fn expected(&self, what: &str) -> AdventError {
+        match self.peek() {
+            Some(token) => {
+                AdventError::parse(format!("expected {}, found '{}'", what, token.text())).at(self.line, token.text())
+            },
+            None => AdventError::parse(format!("expected {}", what)).at_column(self.line.chars().count() + 1),
+        }
+    }
+
+    fn register(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Word(word)) => {
+                self.pos += 1;
+                Ok(word.to_string())
+            },
+            _ => Err(self.expected("a register")),
+        }
+    }
+
+    /// Checks that nothing is left over after a complete instruction or condition.
+    fn finish(&self) -> Result<()> {
+        match self.peek() {
+            Some(token) => Err(AdventError::parse(format!("unexpected '{}'", token.text())).at(self.line, token.text())),
+            None        => Ok(()),
+        }
+    }
+
+    /// `or` binds loosest, then `and`, then `not`.
+    fn condition<T: Value>(&mut self) -> Result<Condition<T>> {
+        let mut condition = self.conjunction()?;
+        while self.eat("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.conjunction()?));
+        }
+        Ok(condition)
+    }
+
+    fn conjunction<T: Value>(&mut self) -> Result<Condition<T>> {
+        let mut condition = self.negation()?;
+        while self.eat("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.negation()?));
+        }
+        Ok(condition)
+    }
+
+    fn negation<T: Value>(&mut self) -> Result<Condition<T>> {
+        let start = self.pos;
+
+        // `not` usually negates what follows, but it could also be a register,
+        // as in `not > 1`. As with parentheses below, try the keyword first.
+        if self.eat("not") {
+            let negated_err = match self.negation() {
+                Ok(condition) => return Ok(Condition::Not(Box::new(condition))),
+                Err(err)      => err,
+            };
+            self.pos = start;
+            return self.comparison().map_err(|err| if err.column > negated_err.column { err } else { negated_err });
+        }
+
+        // A parenthesis could open either a condition, as in `(a > 1 or b > 1)`,
+        // or an expression, as in `(a + 1) > b`. Try the former first, and if
+        // that doesn't work out, go back and try the latter, reporting
+        // whichever attempt got further if neither does.
+        if self.eat("(") {
+            let grouped = self.condition().and_then(|condition| {
+                if self.eat(")") { Ok(condition) } else { Err(self.expected("')'")) }
+            });
+            let grouped_err = match grouped {
+                Ok(condition) => return Ok(condition),
+                Err(err)      => err,
+            };
+            self.pos = start;
+            return self.comparison().map_err(|err| if err.column >= grouped_err.column { err } else { grouped_err });
+        }
+
+        self.comparison()
+    }
+
+    fn comparison<T: Value>(&mut self) -> Result<Condition<T>> {
+        let left = self.expression()?;
+        let comparator = match self.peek().and_then(|token| Comparator::from_symbol(token.text())) {
+            Some(comparator) => comparator,
+            None             => return Err(self.expected("a comparator")),
+        };
+        self.pos += 1;
+        let right = self.expression()?;
+        Ok(Condition::Compare(left, comparator, right))
+    }
+
+    /// Addition and subtraction bind looser than multiplication, division
+    /// and remainder; all of them group from the left.
+    fn expression<T: Value>(&mut self) -> Result<Expr<T>> {
+        let mut expr = self.term()?;
+        loop {
+            let op = if self.eat("+") {
+                BinaryOp::Add
+            } else if self.eat("-") {
+                BinaryOp::Sub
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.term()?));
+        }
+    }
+
+    fn term<T: Value>(&mut self) -> Result<Expr<T>> {
+        let mut expr = self.unary()?;
+        loop {
+            let op = if self.eat("*") {
+                BinaryOp::Mul
+            } else if self.eat("/") {
+                BinaryOp::Div
+            } else if self.eat("%") {
+                BinaryOp::Rem
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.unary()?));
+        }
+    }
+
+    /// A minus sign right before a number is part of the number, so that
+    /// `-10` is a literal, as it always has been, rather than a negation.
+    fn unary<T: Value>(&mut self) -> Result<Expr<T>> {
+        if self.eat("-") {
+            return match self.peek() {
+                Some(Token::Number(digits)) => {
+                    self.pos += 1;
+                    self.literal(digits, true)
+                },
+                _ => Ok(Expr::Neg(Box::new(self.unary()?))),
+            };
+        }
+        self.atom()
+    }
+
+    fn atom<T: Value>(&mut self) -> Result<Expr<T>> {
+        match self.peek() {
+            Some(Token::Number(digits)) => {
+                self.pos += 1;
+                self.literal(digits, false)
+            },
+            Some(Token::Word(word)) => {
+                self.pos += 1;
+                Ok(Expr::Register(word.to_string()))
+            },
+            Some(Token::Symbol("(")) => {
+                self.pos += 1;
+                let expr = self.expression()?;
+                if !self.eat(")") {
+                    return Err(self.expected("')'"));
+                }
+                Ok(expr)
+            },
+            _ => Err(self.expected("a number or register")),
+        }
+    }
+
+    fn literal<T: Value>(&self, digits: &str, negative: bool) -> Result<Expr<T>> {
+        let text = if negative { format!("-{}", digits) } else { digits.to_string() };
+        Num::from_str_radix(&text, 10).map(Expr::Literal).map_err(|_| {
+            AdventError::parse(format!("couldn't parse number '{}'", text)).at(self.line, digits)
+        })
+    }
+}
+
+/// Arithmetic over literals and the values of registers.
+#[derive(Debug, PartialEq)]
+enum Expr<T: Value> {
+    Literal(T),
+    Register(String),
+    Neg(Box<Expr<T>>),
+    Binary(Box<Expr<T>>, BinaryOp, Box<Expr<T>>),
 }
 
 #[derive(Debug, PartialEq)]
-struct Instruction<T: Num + Default + Copy + PartialOrd> {
+struct Instruction<T: Value> {
     register: String,
     operation: Operation,
-    amount: T,
+    amount: Expr<T>,
     condition: Condition<T>
 }
 
-impl<T: Num + Default + Copy + PartialOrd> Instruction<T> {
-    fn parse(text: &str) -> Self {
-        let idx = text.find(" if ").unwrap();
-        let (instr, cond) = (
-            String::from_str(&text[..idx]).unwrap(),
-            String::from_str(&text[idx+4..]).unwrap()
-        );
+impl<T: Value> Instruction<T> {
+    fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser::new(text)?;
 
-        let condition: Condition<T> = Condition::parse(&cond);
-
-        let mut parts = instr.split_whitespace();
-
-        let register = parts.next().unwrap().to_string();
-        let operation = match parts.next().unwrap() {
-            "inc" => Operation::INC,
-            "dec" => Operation::DEC,
-            _ => panic!("Invalid operation")
+        let register = parser.register()?;
+        let operation = match parser.peek() {
+            Some(Token::Word("inc")) => Operation::Inc,
+            Some(Token::Word("dec")) => Operation::Dec,
+            Some(Token::Word("set")) => Operation::Set,
+            Some(Token::Word("mul")) => Operation::Mul,
+            Some(token) => return Err(AdventError::parse(format!("invalid operation '{}'", token.text())).at(text, token.text())),
+            None => return Err(parser.expected("inc, dec, set or mul")),
         };
-        // let amount = parts.next().unwrap().from_str_radix::<T>(10).ok().expect("couldn't parse amount");
-        let amount = Num::from_str_radix(&parts.next().unwrap(), 10).ok().expect("couldn't parse amount");
+        parser.pos += 1;
+        let amount = parser.expression()?;
+        if !parser.eat("if") {
+            return Err(parser.expected("an 'if' condition"));
+        }
+        let condition = parser.condition()?;
+        parser.finish()?;
 
-        Instruction { register, operation, amount, condition }
+        Ok(Instruction { register, operation, amount, condition })
     }
 }
 
 #[test]
 fn test_instruction() {
     assert_eq!(
-        Instruction::parse("b inc 5 if a > 1"),
+        Instruction::parse("b inc 5 if a > 1").unwrap(),
         Instruction {
             register: "b".to_string(),
-            operation: Operation::INC,
-            amount: 5,
-            condition: Condition {
-                register: "a".to_string(),
-                comparator: Comparator::GT,
-                value: 1
-            }
+            operation: Operation::Inc,
+            amount: Expr::Literal(5),
+            condition: Condition::Compare(Expr::Register("a".to_string()), Comparator::Gt, Expr::Literal(1))
+        }
+    );
+    assert_eq!(
+        Instruction::parse("c mul a - -2 if c != b").unwrap(),
+        Instruction {
+            register: "c".to_string(),
+            operation: Operation::Mul,
+            amount: Expr::Binary(Box::new(Expr::Register("a".to_string())), BinaryOp::Sub, Box::new(Expr::Literal(-2))),
+            condition: Condition::Compare(Expr::Register("c".to_string()), Comparator::Neq, Expr::Register("b".to_string()))
         }
     );
+
+    let err = Instruction::<i64>::parse("b jmp 5 if a > 1").unwrap_err();
+    assert_eq!(err.to_string(), "column 3: invalid operation 'jmp'");
+    let err = Instruction::<i64>::parse("b inc 5 if a > 1z").unwrap_err();
+    assert_eq!(err.to_string(), "column 17: unexpected 'z'");
+    let err = Instruction::<i64>::parse("b inc if a > 1").unwrap_err();
+    assert_eq!(err.to_string(), "column 10: expected an 'if' condition, found 'a'");
+    let err = Instruction::<i64>::parse("b set 99999999999999999999 if a > 1").unwrap_err();
+    assert_eq!(err.to_string(), "column 7: couldn't parse number '99999999999999999999'");
+    let err = Instruction::<i64>::parse("5 inc 1 if a > 1").unwrap_err();
+    assert_eq!(err.to_string(), "column 1: expected a register, found '5'");
+    let err = Instruction::<i64>::parse("b inc 5").unwrap_err();
+    assert_eq!(err.to_string(), "column 8: expected an 'if' condition");
 }
 
+/// When an instruction should run: comparisons between expressions,
+/// combined with `and`, `or` and `not`.
 #[derive(Debug, PartialEq)]
-struct Condition<T: Num + Default + Copy + PartialOrd> {
-    register: String,
-    comparator: Comparator,
-    value: T
-}
-
-impl<T: Num + Default + Copy + PartialOrd> Condition<T> {
-    fn parse(text: &str) -> Self {
-        let mut parts = text.split_whitespace();
-
-        let register = parts.next().unwrap().to_string();
-        let comparator = match parts.next().unwrap() {
-            "==" => Comparator::EQ,
-            "!=" => Comparator::NEQ,
-            "<"  => Comparator::LT,
-            "<=" => Comparator::LTE,
-            ">"  => Comparator::GT,
-            ">=" => Comparator::GTE,
-            _ => panic!("bad comparator")
-        };
-        let value = Num::from_str_radix(&parts.next().unwrap(), 10).ok().expect("couldn't parse compare amount");
+enum Condition<T: Value> {
+    Compare(Expr<T>, Comparator, Expr<T>),
+    Not(Box<Condition<T>>),
+    And(Box<Condition<T>>, Box<Condition<T>>),
+    Or(Box<Condition<T>>, Box<Condition<T>>),
+}
 
-        Condition { register, comparator, value }
+impl<T: Value> Condition<T> {
+    #[cfg(test)]
+    fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser::new(text)?;
+        let condition = parser.condition()?;
+        parser.finish()?;
+        Ok(condition)
     }
 }
 
 #[test]
 fn test_condition() {
+    use self::Condition::*;
+
+    let reg = |name: &str| Expr::Register(name.to_string());
+    let compare = |name: &str, comparator, value| Compare(reg(name), comparator, Expr::Literal(value));
+
+    assert_eq!(Condition::parse("a == -3").unwrap(), compare("a", Comparator::Eq, -3));
+    assert_eq!(Condition::parse("tux <= 42").unwrap(), compare("tux", Comparator::Lte, 42));
+    assert_eq!(
+        Condition::parse("not a > 1 and b < 2 or c >= 3").unwrap(),
+        Or(
+            Box::new(And(Box::new(Not(Box::new(compare("a", Comparator::Gt, 1)))), Box::new(compare("b", Comparator::Lt, 2)))),
+            Box::new(compare("c", Comparator::Gte, 3))
+        )
+    );
+    assert_eq!(
+        Condition::parse("not (a > 1 or (b < 2))").unwrap(),
+        Not(Box::new(Or(Box::new(compare("a", Comparator::Gt, 1)), Box::new(compare("b", Comparator::Lt, 2)))))
+    );
+    assert_eq!(
+        Condition::parse("(a + 1) * 2 > -b").unwrap(),
+        Compare(
+            Expr::Binary(
+                Box::new(Expr::Binary(Box::new(reg("a")), BinaryOp::Add, Box::new(Expr::Literal(1)))),
+                BinaryOp::Mul,
+                Box::new(Expr::Literal(2))
+            ),
+            Comparator::Gt,
+            Expr::Neg(Box::new(reg("b")))
+        )
+    );
+
+    assert_eq!(Condition::<i64>::parse("tux =< 42").unwrap_err().to_string(), "column 5: unexpected character '='");
+    assert_eq!(Condition::<i64>::parse("tux").unwrap_err().to_string(), "column 4: expected a comparator");
+    assert_eq!(Condition::<i64>::parse("(a > 1 and b)").unwrap_err().to_string(), "column 13: expected a comparator, found ')'");
+    assert_eq!(Condition::<i64>::parse("(a + 1 > 2").unwrap_err().to_string(), "column 11: expected ')'");
+    assert_eq!(Condition::<i64>::parse("a > 1 b").unwrap_err().to_string(), "column 7: unexpected 'b'");
+    assert_eq!(Condition::<i64>::parse("not").unwrap_err().to_string(), "column 4: expected a number or register");
+}
+
+#[test]
+fn test_keywords_as_registers() {
+    use self::Condition::*;
+
+    let reg = |name: &str| Expr::Register(name.to_string());
+    let compare = |name: &str, comparator, value| Compare(reg(name), comparator, Expr::Literal(value));
+
     assert_eq!(
-        Condition::parse("a == -3"),
-        Condition {
-            register: "a".to_string(),
-            comparator: Comparator::EQ,
-            value: -3
+        Instruction::parse("if inc and if and > 1 and if < 2").unwrap(),
+        Instruction {
+            register: "if".to_string(),
+            operation: Operation::Inc,
+            amount: reg("and"),
+            condition: And(Box::new(compare("and", Comparator::Gt, 1)), Box::new(compare("if", Comparator::Lt, 2)))
         }
     );
     assert_eq!(
-        Condition::parse("tux <= 42"),
-        Condition {
-            register: "tux".to_string(),
-            comparator: Comparator::LTE,
-            value: 42
+        Instruction::parse("or dec 1 if not >= 0 or not or == 1").unwrap(),
+        Instruction {
+            register: "or".to_string(),
+            operation: Operation::Dec,
+            amount: Expr::Literal(1),
+            condition: Or(Box::new(compare("not", Comparator::Gte, 0)), Box::new(Not(Box::new(compare("or", Comparator::Eq, 1)))))
         }
     );
+    assert_eq!(Instruction::<i64>::parse("set set mul if inc > 0").unwrap().amount, reg("mul"));
+
+    let program = Program::parse("and inc 5 if if < 1\nif inc and if and > 1").unwrap();
+    let mut machine = Machine::new();
+    machine.run(&program).unwrap();
+    assert_eq!((machine.register_value("and"), machine.register_value("if")), (Some(&5), Some(&5)));
 }
 
-#[derive(Debug)]
-struct Machine<T: Num + Default + Copy + PartialOrd> {
-    registers: HashMap<String, T>,
-    highest_value: T
+impl<T: Value> Expr<T> {
+    fn registers<'a>(&'a self, found: &mut BTreeSet<&'a str>) {
+        match *self {
+            Expr::Literal(_) => {},
+            Expr::Register(ref register) => { found.insert(register); },
+            Expr::Neg(ref inner) => inner.registers(found),
+            Expr::Binary(ref left, _, ref right) => {
+                left.registers(found);
+                right.registers(found);
+            },
+        }
+    }
+
+    /// The value of the expression, if it can be worked out from the
+    /// registers whose values are known, and doesn't fail.
+    fn constant<F>(&self, value_of: &F) -> Option<T> where F: Fn(&str) -> Option<T> {
+        match *self {
+            Expr::Literal(ref value) => Some(value.clone()),
+            Expr::Register(ref register) => value_of(register),
+            Expr::Neg(ref inner) => BinaryOp::Sub.apply(&T::zero(), &inner.constant(value_of)?).ok(),
+            Expr::Binary(ref left, op, ref right) => op.apply(&left.constant(value_of)?, &right.constant(value_of)?).ok(),
+        }
+    }
 }
 
-impl<T: Num + Default + Copy + PartialOrd> Machine<T> {
-    fn new() -> Self {
-        Machine { registers: HashMap::new(), highest_value: Default::default() }
+impl<T: Value> Condition<T> {
+    fn registers<'a>(&'a self, found: &mut BTreeSet<&'a str>) {
+        match *self {
+            Condition::Compare(ref left, _, ref right) => {
+                left.registers(found);
+                right.registers(found);
+            },
+            Condition::Not(ref inner) => inner.registers(found),
+            Condition::And(ref left, ref right) | Condition::Or(ref left, ref right) => {
+                left.registers(found);
+                right.registers(found);
+            },
+        }
     }
 
-    fn run(&mut self, program: &Program<T>) {
-        for instruction in program.instructions.iter() {
-            self.run_instruction(instruction);
+    /// Whether the condition holds, if that can be worked out from the
+    /// registers whose values are known. One side of an `and` being
+    /// false, or of an `or` being true, is enough.
+    fn constant<F>(&self, value_of: &F) -> Option<bool> where F: Fn(&str) -> Option<T> {
+        match *self {
+            Condition::Compare(ref left, comparator, ref right) => {
+                Some(comparator.holds(&left.constant(value_of)?, &right.constant(value_of)?))
+            },
+            Condition::Not(ref inner) => inner.constant(value_of).map(|holds| !holds),
+            Condition::And(ref left, ref right) => match (left.constant(value_of), right.constant(value_of)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true))            => Some(true),
+                _                                   => None,
+            },
+            Condition::Or(ref left, ref right) => match (left.constant(value_of), right.constant(value_of)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false))        => Some(false),
+                _                                 => None,
+            },
         }
     }
+}
 
-    fn run_instruction(&mut self, inst: &Instruction<T>) {
-        let Instruction { ref register, ref operation, ref amount, ref condition } = *inst;
-        let reg_str = register.to_string();
+/// What can be told about a program without running it. Registers count
+/// as read when they appear in an amount or a condition, and as written
+/// when an instruction targets them.
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    /// Registers that are read but never written, so keep their starting values.
+    pub read_only: Vec<String>,
+    /// Registers that are written but never read, so affect nothing else.
+    pub write_only: Vec<String>,
+    /// Instructions (counting from 1) whose conditions always hold, or never do.
+    pub constant_conditions: Vec<(usize, bool)>,
+    /// For each register, the registers its new values are worked out from.
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+}
 
-        // Touch the register now that we know about it
-        self.registers.entry(reg_str.clone()).or_insert(Default::default());
-        if self.check_condition(condition) {
-            let default = Default::default();
-            let current: T = *self.registers.get(&reg_str).unwrap_or(&default);
-            let new_amount = match operation {
-                &Operation::INC => current + *amount,
-                &Operation::DEC => current - *amount
+impl Analysis {
+    /// The dependency graph in Graphviz's DOT language, with edges
+    /// pointing the way values flow.
+    pub fn to_dot(&self) -> String {
+        let mut registers: BTreeSet<&str> = self.read_only.iter().map(|register| register.as_str()).collect();
+        registers.extend(self.dependencies.keys().map(|register| register.as_str()));
+        let edges: BTreeSet<(&str, &str)> = self.dependencies.iter().flat_map(|(target, sources)| {
+            sources.iter().map(move |source| (source.as_str(), target.as_str()))
+        }).collect();
+
+        let mut dot = "digraph registers {\n".to_string();
+        for register in registers {
+            dot.push_str(&format!("    \"{}\";\n", register));
+        }
+        for (source, target) in edges {
+            dot.push_str(&format!("    \"{}\" -> \"{}\";\n", source, target));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<T: Value> Program<T> {
+    /// Analyzes the program, given the starting values of some registers.
+    /// Registers missing from `start` could hold anything, so conditions
+    /// only count as constant if they hold (or don't) whatever those are.
+    /// Since there are no jumps, a register's value stays known until an
+    /// instruction that might change it in an unknown way.
+    pub fn analyze(&self, start: &HashMap<String, T>) -> Analysis {
+        let mut read: BTreeSet<&str> = BTreeSet::new();
+        let mut dependencies: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut constant_conditions = vec![];
+        let mut known: HashMap<&str, Option<T>> = HashMap::new();
+
+        for (idx, instruction) in self.instructions.iter().enumerate() {
+            let Instruction { ref register, ref operation, ref amount, ref condition } = *instruction;
+
+            // Every operation but `set` works from the register's old value
+            let mut sources = BTreeSet::new();
+            if *operation != Operation::Set {
+                sources.insert(register.as_str());
+            }
+            amount.registers(&mut sources);
+            condition.registers(&mut sources);
+            read.extend(sources.iter().cloned());
+            dependencies.entry(register.clone()).or_default().extend(sources.iter().map(|source| source.to_string()));
+
+            let value = {
+                let value_of = |name: &str| match known.get(name) {
+                    Some(value) => value.clone(),
+                    None        => start.get(name).cloned(),
+                };
+                let holds = condition.constant(&value_of);
+                if let Some(holds) = holds {
+                    constant_conditions.push((idx + 1, holds));
+                }
+                let current = value_of(register);
+                match holds {
+                    Some(false) => current,
+                    Some(true)  => amount.constant(&value_of).and_then(|amount| match *operation {
+                        Operation::Inc => BinaryOp::Add.apply(&current?, &amount).ok(),
+                        Operation::Dec => BinaryOp::Sub.apply(&current?, &amount).ok(),
+                        Operation::Set => Some(amount),
+                        Operation::Mul => BinaryOp::Mul.apply(&current?, &amount).ok(),
+                    }),
+                    None        => None,
+                }
             };
-            self.record_value(reg_str, new_amount);
+            known.insert(register, value);
+        }
+
+        let read_only = read.iter().filter(|register| !dependencies.contains_key(**register)).map(|register| register.to_string()).collect();
+        let write_only = dependencies.keys().filter(|register| !read.contains(register.as_str())).cloned().collect();
+        Analysis { read_only, write_only, constant_conditions, dependencies }
+    }
+}
+
+#[test]
+fn test_analyze() {
+    let text = "a set x * 2 if x > 1\n\
+                b inc a if c > 0\n\
+                c dec 1 if a == 10 or c > b\n\
+                d inc 1 if c > 0 and x < 0\n\
+                e inc b if 1 > 2\n\
+                a dec 1 if a / y > 0\n\
+                f set 3 if x > 0";
+
+    let program: Program<i64> = Program::parse(text).unwrap();
+    let start: HashMap<String, i64> = vec![("x".to_string(), 5)].into_iter().collect();
+    let analysis = program.analyze(&start);
+
+    assert_eq!(analysis.read_only, vec!["x", "y"]);
+    assert_eq!(analysis.write_only, vec!["f"]);
+    assert_eq!(analysis.constant_conditions, vec![(1, true), (3, true), (4, false), (5, false), (7, true)]);
+    let sources = |register: &str| analysis.dependencies[register].iter().map(|source| source.as_str()).collect::<Vec<_>>();
+    assert_eq!(sources("a"), vec!["a", "x", "y"]);
+    assert_eq!(sources("c"), vec!["a", "b", "c"]);
+    assert_eq!(sources("d"), vec!["c", "d", "x"]);
+    assert_eq!(sources("e"), vec!["b", "e"]);
+    assert_eq!(sources("f"), vec!["x"]);
+
+    // With every register starting at zero, every condition is known
+    let zeroed: HashMap<String, i64> = ["a", "b", "c", "d", "e", "f", "x", "y"].iter().map(|r| (r.to_string(), 0)).collect();
+    assert_eq!(program.analyze(&zeroed).constant_conditions.len(), 6);
+
+    // Incrementing a register reads it, even if nothing else does
+    let program: Program<i64> = Program::parse("d inc 1 if x > 0").unwrap();
+    let analysis = program.analyze(&HashMap::new());
+    assert_eq!((&analysis.read_only, &analysis.write_only), (&vec!["x".to_string()], &vec![]));
+    assert_eq!(analysis.to_dot(), "digraph registers {\n    \"d\";\n    \"x\";\n    \"d\" -> \"d\";\n    \"x\" -> \"d\";\n}\n");
+
+    let program: Program<i64> = Program::parse("b inc a if a > 0\nc set 1 if b != 0").unwrap();
+    assert_eq!(program.analyze(&HashMap::new()).to_dot(), "digraph registers {\n    \"a\";\n    \"b\";\n    \"c\";\n    \"a\" -> \"b\";\n    \"b\" -> \"b\";\n    \"b\" -> \"c\";\n}\n");
+}
+
+/// What happened when one instruction ran: whether its condition held,
+/// and the value of its register before and after.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step<T: Value> {
+    pub instruction: usize,
+    pub register: String,
+    pub fired: bool,
+    pub before: T,
+    pub after: T,
+}
+
+/// Everything a traced `Machine` did: a `Step` for each instruction run,
+/// and for each register, the value it took on at each instruction that
+/// created or wrote it. Instructions are numbered from 1, like lines.
+#[derive(Debug, Default)]
+pub struct Trace<T: Value> {
+    steps: Vec<Step<T>>,
+    history: HashMap<String, Vec<(usize, T)>>,
+}
+
+impl<T: Value> Trace<T> {
+    pub fn steps(&self) -> &[Step<T>] {
+        &self.steps
+    }
+
+    /// The values a register has held, with the instructions that
+    /// gave them to it, starting with the zero it was created with.
+    pub fn history(&self, register: &str) -> &[(usize, T)] {
+        self.history.get(register).map_or(&[], |history| history.as_slice())
+    }
+
+    fn record(&mut self, register: &str, instruction: usize, value: T) {
+        self.history.entry(register.to_string()).or_default().push((instruction, value));
+    }
+
+    /// The first instruction to write the highest value ever written.
+    pub fn highest(&self) -> Option<&Step<T>> {
+        self.steps.iter().filter(|step| step.fired).fold(None, |highest, step| match highest {
+            Some(highest) if step.after <= highest.after => Some(highest),
+            _                                            => Some(step),
+        })
+    }
+
+    /// A register's history as CSV, one row per value, for plotting.
+    pub fn to_csv(&self, register: &str) -> String {
+        let mut csv = "instruction,value\n".to_string();
+        for &(instruction, ref value) in self.history(register) {
+            csv.push_str(&format!("{},{}\n", instruction, value));
+        }
+        csv
+    }
+}
+
+/// Register names, numbered in the order they're first seen.
+#[derive(Debug, Default)]
+struct Names {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl Names {
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
         }
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
     }
+}
+
+type CompiledExpr<T> = Box<dyn Fn(&mut Registers<T>) -> Result<T>>;
+type CompiledCondition<T> = Box<dyn Fn(&mut Registers<T>) -> Result<bool>>;
 
-    fn check_condition(&mut self, cond: &Condition<T>) -> bool {
-        let Condition { ref register, ref comparator, ref value } = *cond;
-        let reg_str = register.to_string();
+impl<T: Value> Expr<T> {
+    fn compile(&self, names: &mut Names) -> CompiledExpr<T> {
+        match *self {
+            Expr::Literal(ref value) => {
+                let value = value.clone();
+                Box::new(move |_| Ok(value.clone()))
+            },
+            Expr::Register(ref register) => {
+                let index = names.intern(register);
+                Box::new(move |registers| Ok(registers.read(index)))
+            },
+            Expr::Neg(ref inner) => {
+                let inner = inner.compile(names);
+                Box::new(move |registers| BinaryOp::Sub.apply(&T::zero(), &inner(registers)?))
+            },
+            Expr::Binary(ref left, op, ref right) => {
+                let (left, right) = (left.compile(names), right.compile(names));
+                Box::new(move |registers| {
+                    let left = left(registers)?;
+                    op.apply(&left, &right(registers)?)
+                })
+            },
+        }
+    }
+}
+
+impl<T: Value> Condition<T> {
+    /// `and` and `or` only look at their right-hand side when they need to,
+    /// so a condition like `b != 0 and a / b > 1` can guard a division.
+    fn compile(&self, names: &mut Names) -> CompiledCondition<T> {
+        match *self {
+            Condition::Compare(ref left, comparator, ref right) => {
+                let (left, right) = (left.compile(names), right.compile(names));
+                Box::new(move |registers| {
+                    let left = left(registers)?;
+                    Ok(comparator.holds(&left, &right(registers)?))
+                })
+            },
+            Condition::Not(ref inner) => {
+                let inner = inner.compile(names);
+                Box::new(move |registers| Ok(!inner(registers)?))
+            },
+            Condition::And(ref left, ref right) => {
+                let (left, right) = (left.compile(names), right.compile(names));
+                Box::new(move |registers| Ok(left(registers)? && right(registers)?))
+            },
+            Condition::Or(ref left, ref right) => {
+                let (left, right) = (left.compile(names), right.compile(names));
+                Box::new(move |registers| Ok(left(registers)? || right(registers)?))
+            },
+        }
+    }
+}
+
+struct CompiledInstruction<T: Value> {
+    register: usize,
+    operation: Operation,
+    amount: CompiledExpr<T>,
+    condition: CompiledCondition<T>,
+}
+
+impl<T: Value> CompiledInstruction<T> {
+    /// Runs the instruction, returning whether its condition held.
+    fn execute(&self, registers: &mut Registers<T>) -> Result<bool> {
+        // Touch the register now that we know about it
+        let current = registers.read(self.register);
+        if !(self.condition)(registers)? {
+            return Ok(false);
+        }
+        let amount = (self.amount)(registers)?;
+        let value = match self.operation {
+            Operation::Inc => BinaryOp::Add.apply(&current, &amount)?,
+            Operation::Dec => BinaryOp::Sub.apply(&current, &amount)?,
+            Operation::Set => amount,
+            Operation::Mul => BinaryOp::Mul.apply(&current, &amount)?,
+        };
+        registers.write(self.register, value);
+        Ok(true)
+    }
+}
+
+/// A program with its register names swapped for indices into a
+/// `Registers` file, and each instruction built into closures, so it
+/// can be run many times without looking anything up by name.
+pub struct Compiled<T: Value> {
+    names: Vec<String>,
+    instructions: Vec<CompiledInstruction<T>>,
+}
+
+impl<T: Value> Compiled<T> {
+    /// The names of the registers, in the order of their indices.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
 
-        // Touch this register now that we know it exists.
-        self.registers.entry(reg_str.clone()).or_insert(Default::default());
-        let default = Default::default();
-        let current: T = *self.registers.get(&reg_str).unwrap_or(&default);
-        match comparator {
-            &Comparator::EQ  => current == *value,
-            &Comparator::NEQ => current != *value,
-            &Comparator::LT  => current <  *value,
-            &Comparator::LTE => current <= *value,
-            &Comparator::GT  => current >  *value,
-            &Comparator::GTE => current >= *value
+    /// A fresh set of registers for this program to run on.
+    pub fn registers(&self) -> Registers<T> {
+        Registers {
+            values: vec![Default::default(); self.names.len()],
+            touched: vec![false; self.names.len()],
+            created: vec![],
+            highest: Default::default(),
         }
     }
 
-    fn record_value(&mut self, register: String, value: T) {
-        self.registers.insert(register, value);
-        if value > self.highest_value {
-            self.highest_value = value;
+    /// Runs every instruction in turn. Errors say which instruction
+    /// (counting from 1) caused them.
+    pub fn run(&self, registers: &mut Registers<T>) -> Result<()> {
+        for (idx, instruction) in self.instructions.iter().enumerate() {
+            instruction.execute(registers).map_err(|err| err.on_line(idx + 1))?;
+        }
+        Ok(())
+    }
+}
+
+/// The registers a `Compiled` program runs on, by index. Like a
+/// `Machine`'s, registers only count as existing once they've been
+/// looked at.
+pub struct Registers<T: Value> {
+    values: Vec<T>,
+    touched: Vec<bool>,
+    /// Registers looked at for the first time, not yet handled by a `Machine`.
+    created: Vec<usize>,
+    highest: T,
+}
+
+impl<T: Value> Registers<T> {
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if self.touched[index] { Some(&self.values[index]) } else { None }
+    }
+
+    /// The highest value ever written to any register, or zero.
+    pub fn highest(&self) -> &T {
+        &self.highest
+    }
+
+    fn read(&mut self, index: usize) -> T {
+        if !self.touched[index] {
+            self.touched[index] = true;
+            self.created.push(index);
+        }
+        self.values[index].clone()
+    }
+
+    fn write(&mut self, index: usize, value: T) {
+        if value > self.highest {
+            self.highest = value.clone();
+        }
+        self.values[index] = value;
+    }
+}
+
+#[derive(Debug)]
+pub struct Machine<T: Value> {
+    registers: HashMap<String, T>,
+    highest_value: T,
+    trace: Option<Trace<T>>,
+}
+
+impl<T: Value> Machine<T> {
+    pub fn new() -> Self {
+        Machine { registers: HashMap::new(), highest_value: Default::default(), trace: None }
+    }
+
+    /// A machine that keeps a `Trace` of what it does, which is
+    /// slower and takes more memory, but can answer more questions.
+    pub fn traced() -> Self {
+        Machine { trace: Some(Trace::default()), ..Machine::new() }
+    }
+
+    /// Runs every instruction in turn, starting from the registers left
+    /// by earlier runs. Errors, like dividing by zero, say which
+    /// instruction (counting from 1) caused them.
+    pub fn run(&mut self, program: &Program<T>) -> Result<()> {
+        self.run_compiled(&program.compile())
+    }
+
+    /// Like `run`, for a program that's already been compiled.
+    pub fn run_compiled(&mut self, compiled: &Compiled<T>) -> Result<()> {
+        let mut registers = compiled.registers();
+        for (index, name) in compiled.names.iter().enumerate() {
+            if let Some(value) = self.registers.get(name) {
+                registers.values[index] = value.clone();
+                registers.touched[index] = true;
+            }
+        }
+
+        let result = match self.trace {
+            Some(ref mut trace) => Self::run_traced(compiled, &mut registers, trace),
+            None                => compiled.run(&mut registers),
+        };
+
+        // Keep whatever happened, even if the program stopped early
+        for (index, name) in compiled.names.iter().enumerate() {
+            if let Some(value) = registers.get(index) {
+                self.registers.insert(name.clone(), value.clone());
+            }
+        }
+        if registers.highest > self.highest_value {
+            self.highest_value = registers.highest;
+        }
+        result
+    }
+
+    fn run_traced(compiled: &Compiled<T>, registers: &mut Registers<T>, trace: &mut Trace<T>) -> Result<()> {
+        for (idx, instruction) in compiled.instructions.iter().enumerate() {
+            let target = instruction.register;
+            let before = registers.values[target].clone();
+            let fired = instruction.execute(registers).map_err(|err| err.on_line(idx + 1))?;
+            let after = registers.values[target].clone();
+
+            for index in registers.created.drain(..) {
+                trace.record(&compiled.names[index], idx + 1, Default::default());
+            }
+            if fired {
+                trace.record(&compiled.names[target], idx + 1, after.clone());
+            }
+            trace.steps.push(Step { instruction: idx + 1, register: compiled.names[target].clone(), fired, before, after });
         }
+        Ok(())
+    }
+
+    /// The trace kept by a `traced` machine.
+    pub fn trace(&self) -> Option<&Trace<T>> {
+        self.trace.as_ref()
     }
 
     #[cfg(test)]
@@ -193,23 +1037,151 @@ impl<T: Num + Default + Copy + PartialOrd> Machine<T> {
         self.registers.get(register)
     }
 
-    fn registers(&self) -> collections::hash_map::Iter<String, T> {
+    pub fn registers(&self) -> collections::hash_map::Iter<String, T> {
         self.registers.iter()
     }
 
-    fn highest_value(&self) -> T {
-        self.highest_value
+    pub fn highest_value(&self) -> T {
+        self.highest_value.clone()
     }
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/08.txt").expect("expected input 08.txt");
-    let program: Program<i64> = Program::parse(&input);
-    let mut machine = Machine::new();
-    machine.run(&program);
-    let (max_key, max_val) = machine.registers().max_by_key(|&(_key, val)| val).unwrap();
-    println!("Part 1: {} in register {}", max_val, max_key);
-    println!("Part 2: Highest ever value was {}", machine.highest_value());
+impl<T: Value> Default for Machine<T> {
+    fn default() -> Self {
+        Machine::new()
+    }
+}
+
+#[test]
+fn test_evaluate() {
+    let mut names = Names::default();
+    let a = names.intern("a");
+    let mut registers = Compiled::<i64> { names: vec!["a".to_string(), "b".to_string()], instructions: vec![] }.registers();
+    registers.write(a, 7);
+    let mut eval = |text: &str| {
+        let mut parser = Parser::new(text).unwrap();
+        let expr: Expr<i64> = parser.expression().unwrap();
+        expr.compile(&mut names)(&mut registers)
+    };
+
+    assert_eq!(eval("1 + 2 * -3 - a % 4").unwrap(), -8);
+    assert_eq!(eval("(1 + 2) * 3 - -a").unwrap(), 16);
+    assert_eq!(eval("a / 2 / 2").unwrap(), 1);
+    assert_eq!(eval("--a - -(a - 1)").unwrap(), 13);
+    assert_eq!(eval("a % b").unwrap_err().to_string(), "division by zero");
+    assert_eq!(eval("9223372036854775807 + a").unwrap_err().to_string(), "overflow computing 9223372036854775807 + 7");
+    assert_eq!(eval("-(-9223372036854775807 - 1)").unwrap_err().to_string(), "overflow computing 0 - -9223372036854775808");
+    assert_eq!(eval("(-9223372036854775807 - 1) % -1").unwrap_err().to_string(), "overflow computing -9223372036854775808 % -1");
+}
+
+#[test]
+fn test_compiled() {
+    let text = "b inc 5 if a > 1\n\
+                a inc 1 if b < 5\n\
+                c dec -10 if a >= 1\n\
+                c inc -20 if c == 10 or d > 0";
+
+    let program: Program<i64> = Program::parse(text).unwrap();
+    let compiled = program.compile();
+    assert_eq!(compiled.names(), &["b", "a", "c", "d"]);
+
+    let mut registers = compiled.registers();
+    compiled.run(&mut registers).unwrap();
+    assert_eq!(registers.get(1), Some(&1));
+    assert_eq!(registers.get(2), Some(&-10));
+    // The `or` never needed to look at d
+    assert_eq!(registers.get(3), None);
+    assert_eq!(registers.highest(), &10);
+
+    // Running again picks up where the last run left off
+    compiled.run(&mut registers).unwrap();
+    assert_eq!(registers.get(1), Some(&2));
+    assert_eq!(registers.get(2), Some(&0));
+}
+
+/// Runs the program with registers of type `T`: `i64` by default, or
+/// `BigInt` for programs whose values don't fit.
+pub struct Day08<T = i64> {
+    registers: PhantomData<T>,
+    traced: bool,
+}
+
+impl<T> Day08<T> {
+    /// Also works out which instruction wrote the highest value, by
+    /// tracing the whole run of the program for part 2.
+    pub fn traced() -> Self {
+        Day08 { registers: PhantomData, traced: true }
+    }
+}
+
+impl<T> Default for Day08<T> {
+    fn default() -> Self {
+        Day08 { registers: PhantomData, traced: false }
+    }
+}
+
+/// The largest value left in any register, and the register holding it.
+pub struct LargestRegister<T> {
+    register: String,
+    value: T,
+}
+
+impl<T: fmt::Display> fmt::Display for LargestRegister<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// The highest value any register ever held, and, if the run was traced,
+/// the instruction that wrote it, unless no register ever rose above zero.
+pub struct HighestValue<T> {
+    value: T,
+    instruction: Option<usize>,
+}
+
+impl<T: fmt::Display> fmt::Display for HighestValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<T: Value> Solution for Day08<T> {
+    const DAY: u32 = 8;
+
+    type Input = Compiled<T>;
+    type Part1 = LargestRegister<T>;
+    type Part2 = HighestValue<T>;
+
+    fn parse(&self, input: &str) -> Result<Compiled<T>> {
+        Ok(Program::parse(input)?.compile())
+    }
+
+    fn part1(&self, program: &Compiled<T>) -> Result<LargestRegister<T>> {
+        let mut machine = Machine::new();
+        machine.run_compiled(program)?;
+        let (max_key, max_val) = machine.registers().max_by_key(|&(_key, val)| val)
+            .ok_or_else(|| AdventError::solve("the program has no registers"))?;
+        Ok(LargestRegister { register: max_key.clone(), value: max_val.clone() })
+    }
+
+    fn part2(&self, program: &Compiled<T>) -> Result<HighestValue<T>> {
+        let mut machine = if self.traced { Machine::traced() } else { Machine::new() };
+        machine.run_compiled(program)?;
+        let value = machine.highest_value();
+        let instruction = machine.trace().and_then(Trace::highest).filter(|step| step.after == value).map(|step| step.instruction);
+        Ok(HighestValue { value, instruction })
+    }
+
+    fn describe_part1(&self, answer: &LargestRegister<T>) -> String {
+        format!("{} in register {}", answer.value, answer.register)
+    }
+
+    fn describe_part2(&self, answer: &HighestValue<T>) -> String {
+        match answer.instruction {
+            Some(instruction) => format!("Highest ever value was {}, written by instruction {}", answer.value, instruction),
+            None              => format!("Highest ever value was {}", answer.value),
+        }
+    }
 }
 
 #[test]
@@ -219,12 +1191,96 @@ fn test_program() {
                 c dec -10 if a >= 1\n\
                 c inc -20 if c == 10";
 
-    let program: Program<i64> = Program::parse(&text);
+    let program: Program<i64> = Program::parse(text).unwrap();
     let mut machine = Machine::new();
-    machine.run(&program);
+    machine.run(&program).unwrap();
 
     assert_eq!(machine.register_value("a"), Some(&1));
     assert_eq!(machine.register_value("b"), Some(&0));
     assert_eq!(machine.register_value("c"), Some(&-10));
     assert_eq!(machine.highest_value(), 10);
 }
+
+#[test]
+fn test_expression_program() {
+    let text = "x set 7 if x == 0\n\
+                y inc x * 2 if x > 1 and not x > 10\n\
+                z mul y if z != 0\n\
+                z set y / (x - 7) if x != 7 and y / (x - 7) > 0\n\
+                w set y % 5 - x if (x + y) == 21 or w / z > 0";
+
+    let program: Program<i64> = Program::parse(text).unwrap();
+    let mut machine = Machine::new();
+    machine.run(&program).unwrap();
+
+    assert_eq!(machine.register_value("x"), Some(&7));
+    assert_eq!(machine.register_value("y"), Some(&14));
+    assert_eq!(machine.register_value("z"), Some(&0));
+    assert_eq!(machine.register_value("w"), Some(&-3));
+    assert_eq!(machine.highest_value(), 14);
+
+    let program: Program<i64> = Program::parse("a inc 1 if a == 0\na set 1 / b if a == 1").unwrap();
+    let err = Machine::new().run(&program).unwrap_err();
+    assert_eq!(err.to_string(), "line 2: division by zero");
+}
+
+#[test]
+fn test_trace() {
+    let text = "b inc 5 if a > 1\n\
+                a inc 1 if b < 5\n\
+                c dec -10 if a >= 1\n\
+                c inc -20 if c == 10";
+
+    let program: Program<i64> = Program::parse(text).unwrap();
+    let mut machine = Machine::traced();
+    machine.run(&program).unwrap();
+    let trace = machine.trace().unwrap();
+
+    assert_eq!(trace.steps().len(), 4);
+    assert_eq!(trace.steps()[0], Step { instruction: 1, register: "b".to_string(), fired: false, before: 0, after: 0 });
+    assert_eq!(trace.steps()[3], Step { instruction: 4, register: "c".to_string(), fired: true, before: 10, after: -10 });
+    assert_eq!(trace.highest().map(|step| step.instruction), Some(3));
+    assert_eq!(trace.history("a"), &[(1, 0), (2, 1)]);
+    assert_eq!(trace.history("z"), &[]);
+    assert_eq!(trace.to_csv("c"), "instruction,value\n3,0\n3,10\n4,-10\n");
+
+    assert!(Machine::<i64>::new().trace().is_none());
+}
+
+#[test]
+fn test_bigint_registers() {
+    use self::num::BigInt;
+
+    let text = "a inc 9223372036854775807 if a == 0\n\
+                a inc 1 if a > 0\n\
+                b set a * a if b == 0";
+
+    let program: Program<i64> = Program::parse(text).unwrap();
+    let err = Machine::new().run(&program).unwrap_err();
+    assert_eq!(err.to_string(), "line 2: overflow computing 9223372036854775807 + 1");
+
+    let program: Program<BigInt> = Program::parse(text).unwrap();
+    let mut machine = Machine::new();
+    machine.run(&program).unwrap();
+    assert_eq!(machine.register_value("a").map(|a| a.to_string()), Some("9223372036854775808".to_string()));
+    assert_eq!(machine.highest_value().to_string(), "85070591730234615865843651857942052864");
+
+    let day = Day08::<BigInt>::default();
+    let answer = day.part1(&day.parse("x set 100000000000000000000 if x == 0").unwrap()).unwrap();
+    assert_eq!(day.describe_part1(&answer), "100000000000000000000 in register x");
+}
+
+#[test]
+fn test_traced_part2() {
+    let text = "b inc 5 if a > 1\na inc 1 if b < 5\nc dec -10 if a >= 1\nc inc -20 if c == 10";
+
+    let day = Day08::<i64>::default();
+    let answer = day.part2(&day.parse(text).unwrap()).unwrap();
+    assert_eq!((answer.value, answer.instruction), (10, None));
+    assert_eq!(day.describe_part2(&answer), "Highest ever value was 10");
+
+    let day = Day08::<i64>::traced();
+    let answer = day.part2(&day.parse(text).unwrap()).unwrap();
+    assert_eq!((answer.value, answer.instruction), (10, Some(3)));
+    assert_eq!(day.describe_part2(&answer), "Highest ever value was 10, written by instruction 3");
+}
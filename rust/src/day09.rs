@@ -1,60 +1,85 @@
-use common;
+use error::{AdventError, Result};
+use solution::Solution;
 use std::str::Chars;
 use std::iter::Peekable;
+use std::mem;
 
 enum ParserState {
     NORMAL, GARBAGE
 }
 
+/// A piece of the stream: a group opening or closing, or a run of garbage
+/// holding its uncancelled characters.
+#[derive(Debug, PartialEq)]
+pub enum Token {
+    Open,
+    Close,
+    Garbage(String),
+}
+
 struct Parser<'a> {
     input: Peekable<Chars<'a>>,
-    score: u32,
-    garbage_count: u32,
-    stack: Vec<u32>,
+    column: usize,
+    tokens: Vec<Token>,
+    garbage: String,
+    depth: usize,
     state: ParserState,
 }
 
 impl<'a> Parser<'a> {
-    fn score(input: &'a str) -> (u32, u32) {
-        Self::new(input).get_score()
+    fn tokenise(input: &'a str) -> Result<Vec<Token>> {
+        Self::new(input).get_tokens()
     }
 
     fn new(input: &'a str) -> Self {
         Parser {
             input: input.chars().peekable(),
-            score: 0,
-            garbage_count: 0,
-            stack: vec![],
+            column: 1,
+            tokens: vec![],
+            garbage: String::new(),
+            depth: 0,
             state: ParserState::NORMAL,
         }
     }
 
-    fn get_score(&mut self) -> (u32, u32) {
+    fn get_tokens(mut self) -> Result<Vec<Token>> {
         while let Some(_) = self.input.peek() {
             match self.state {
-                ParserState::NORMAL  => self.parse_normal(),
+                ParserState::NORMAL  => self.parse_normal()?,
                 ParserState::GARBAGE => self.parse_garbage()
             };
         }
 
-        (self.score, self.garbage_count)
+        match self.state {
+            ParserState::GARBAGE => return Err(self.error("unterminated garbage")),
+            ParserState::NORMAL if self.depth > 0 => return Err(self.error("unclosed group")),
+            ParserState::NORMAL => {}
+        }
+
+        Ok(self.tokens)
     }
 
-    fn parse_normal(&mut self) {
+    fn parse_normal(&mut self) -> Result<()> {
         if self.consume('{') {
-            let current_group_score = *self.stack.last().unwrap_or(&0);
-            let new_group_score = current_group_score + 1;
-            self.stack.push(new_group_score);
-        } else if self.consume('}') {
-            let current_group_score = self.stack.pop().expect("found a close group with no group");
-            self.score += current_group_score;
+            self.depth += 1;
+            self.tokens.push(Token::Open);
+        } else if self.check('}') {
+            if self.depth == 0 {
+                return Err(self.error("found a close group with no group"));
+            }
+            self.consume_any();
+            self.depth -= 1;
+            self.tokens.push(Token::Close);
         } else if self.consume('<') {
             self.state = ParserState::GARBAGE;
         } else if self.consume(',') {
             // nothing special to do for commas
         } else {
-            panic!("found an unexpected character!")
+            let c = self.input.peek().cloned().unwrap_or_default();
+            return Err(self.error(&format!("found an unexpected character '{}'", c)));
         }
+
+        Ok(())
     }
 
     fn parse_garbage(&mut self) {
@@ -62,42 +87,103 @@ impl<'a> Parser<'a> {
             self.consume_any();
         } else if self.consume('>') {
             self.state = ParserState::NORMAL;
-        } else {
-            self.garbage_count += 1;
-            self.consume_any();
+            self.tokens.push(Token::Garbage(mem::take(&mut self.garbage)));
+        } else if let Some(c) = self.input.next() {
+            self.column += 1;
+            self.garbage.push(c);
         }
     }
 
+    fn check(&mut self, check: char) -> bool {
+        self.input.peek() == Some(&check)
+    }
+
     fn consume(&mut self, check: char) -> bool {
-        match self.input.peek() {
-            Some(&c) if c == check => {
-                self.input.next();
-                true
-            },
-            _ => false
+        if self.check(check) {
+            self.consume_any();
+            true
+        } else {
+            false
         }
     }
 
     fn consume_any(&mut self) {
-        self.input.next();
+        if self.input.next().is_some() {
+            self.column += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> AdventError {
+        AdventError::parse(message).at_column(self.column)
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+
+    type Input = Vec<Token>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Token>> {
+        Parser::tokenise(input.trim())
+    }
+
+    /// Each group scores one more than the group it's in.
+    fn part1(&self, tokens: &Vec<Token>) -> Result<u32> {
+        let mut depth = 0;
+        let mut score = 0;
+        for token in tokens {
+            match *token {
+                Token::Open => depth += 1,
+                Token::Close => {
+                    score += depth;
+                    depth -= 1;
+                },
+                Token::Garbage(_) => {}
+            }
+        }
+        Ok(score)
+    }
+
+    fn part2(&self, tokens: &Vec<Token>) -> Result<u32> {
+        Ok(tokens.iter().map(|token| match *token {
+            Token::Garbage(ref garbage) => garbage.chars().count() as u32,
+            _ => 0,
+        }).sum())
+    }
+
+    fn describe_part1(&self, score: &u32) -> String {
+        format!("Score is {}", score)
+    }
+
+    fn describe_part2(&self, garbage_count: &u32) -> String {
+        format!("Removed {} garbage chars", garbage_count)
     }
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/09.txt").expect("expected input 09.txt");
-    let (score, garbage_count) = Parser::score(&input);
-    println!("Part 1: Score is {}", score);
-    println!("Part 2: Removed {} garbage chars", garbage_count);
+#[cfg(test)]
+fn score(input: &str) -> (u32, u32) {
+    let tokens = Day09.parse(input).unwrap();
+    (Day09.part1(&tokens).unwrap(), Day09.part2(&tokens).unwrap())
 }
 
 #[test]
 fn test_day9() {
-    assert_eq!(Parser::score("{}"), (1, 0));
-    assert_eq!(Parser::score("{{{}}}"), (6, 0));
-    assert_eq!(Parser::score("{{},{}}"), (5, 0));
-    assert_eq!(Parser::score("{{{},{},{{}}}}"), (16, 0));
-    assert_eq!(Parser::score("{<a>,<a>,<a>,<a>}"), (1, 4));
-    assert_eq!(Parser::score("{{<ab>},{<ab>},{<ab>},{<ab>}}"), (9, 8));
-    assert_eq!(Parser::score("{{<!!>},{<!!>},{<!!>},{<!!>}}"), (9, 0));
-    assert_eq!(Parser::score("{{<a!>},{<a!>},{<a!>},{<ab>}}"), (3, 17));
+    assert_eq!(score("{}"), (1, 0));
+    assert_eq!(score("{{{}}}"), (6, 0));
+    assert_eq!(score("{{},{}}"), (5, 0));
+    assert_eq!(score("{{{},{},{{}}}}"), (16, 0));
+    assert_eq!(score("{<a>,<a>,<a>,<a>}"), (1, 4));
+    assert_eq!(score("{{<ab>},{<ab>},{<ab>},{<ab>}}"), (9, 8));
+    assert_eq!(score("{{<!!>},{<!!>},{<!!>},{<!!>}}"), (9, 0));
+    assert_eq!(score("{{<a!>},{<a!>},{<a!>},{<ab>}}"), (3, 17));
+
+    assert_eq!(Day09.parse("{<a!>b>,{}}").unwrap(), vec![Token::Open, Token::Garbage("ab".to_string()), Token::Open, Token::Close, Token::Close]);
+
+    assert_eq!(Day09.parse("{}}").unwrap_err().to_string(), "column 3: found a close group with no group");
+    assert_eq!(Day09.parse("{x}").unwrap_err().to_string(), "column 2: found an unexpected character 'x'");
+    assert_eq!(Day09.parse("{{}").unwrap_err().to_string(), "column 4: unclosed group");
 }
@@ -1,4 +1,5 @@
-use common;
+use error::{AdventError, Result};
+use solution::Solution;
 use std::str::FromStr;
 
 pub struct KnotHasher {
@@ -8,6 +9,7 @@ pub struct KnotHasher {
 }
 
 impl KnotHasher {
+    /// Computes the full 64-round knot hash of `input` as a hex string.
     pub fn hash(input: &str) -> String {
         let mut lens: Vec<u8> = input.trim().as_bytes().into();
         lens.extend([17u8, 31u8, 73u8, 47u8, 23u8].iter());
@@ -24,11 +26,13 @@ impl KnotHasher {
         dense.join("")
     }
 
-    fn new(vec: Vec<u8>) -> Self {
+    /// Creates a hasher that ties knots in `vec`.
+    pub fn new(vec: Vec<u8>) -> Self {
         KnotHasher { vec, pos: 0, skip: 0 }
     }
 
-    fn run(&mut self, lengths: &[u8]) {
+    /// Runs a single round of knot-tying with the given lengths.
+    pub fn run(&mut self, lengths: &[u8]) {
         for len in lengths {
             self.process_length(*len as usize);
         }
@@ -54,28 +58,53 @@ impl KnotHasher {
         result
     }
 
-    fn to_slice(&self) -> &[u8] {
+    pub fn to_slice(&self) -> &[u8] {
         &self.vec
     }
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/10.txt").expect("expected input 10.txt");
+pub struct Day10;
 
-    { // Part 1
-        let lens: Vec<u8> = input.split(',').map(|s| u8::from_str(&s).unwrap()).collect();
+/// The input read both ways: Part 1 ties knots with the comma-separated
+/// lengths, and Part 2 hashes the raw text.
+pub struct Lengths {
+    lengths: Vec<u8>,
+    text: String,
+}
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Lengths;
+    type Part1 = u64;
+    type Part2 = String;
+
+    fn parse(&self, input: &str) -> Result<Lengths> {
+        let text = input.trim().to_string();
+        Ok(Lengths { lengths: parse_lengths(&text)?, text })
+    }
+
+    fn part1(&self, input: &Lengths) -> Result<u64> {
         let mut hasher = KnotHasher::new(gen_list());
-        hasher.run(&lens);
+        hasher.run(&input.lengths);
         let slice = hasher.to_slice();
-        println!("Part 1: {}", slice[0] as u64 * slice[1] as u64);
+        Ok(slice[0] as u64 * slice[1] as u64)
     }
 
-    {
-        // Part 2
-        println!("Part 2: {}", KnotHasher::hash(&input));
+    fn part2(&self, input: &Lengths) -> Result<String> {
+        Ok(KnotHasher::hash(&input.text))
     }
 }
 
+/// Part 1 reads the input as comma-separated lengths; Part 2 uses its raw bytes.
+fn parse_lengths(input: &str) -> Result<Vec<u8>> {
+    input.split(',').map(|s| {
+        u8::from_str(s.trim()).map_err(|_| {
+            AdventError::parse(format!("couldn't parse length '{}'; must be 0-255", s.trim())).at(input, s)
+        })
+    }).collect()
+}
+
 fn gen_list() -> Vec<u8> {
     let mut v = Vec::with_capacity(256);
     for i in 0..256 {
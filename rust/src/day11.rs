@@ -1,9 +1,11 @@
-use common;
+use error::{self, AdventError};
+use solution::Solution;
+use std::fmt;
 use std::ops::Add;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum HexDirection {
+pub enum HexDirection {
     N, NE, SE, S, SW, NW
 }
 
@@ -31,23 +33,25 @@ fn test_hex_direction() {
     assert!("ok".parse::<HexDirection>().is_err());
 }
 
+/// A position on a hex grid, in cube coordinates.
 #[derive(Debug, PartialEq, Clone, Copy)]
-struct Hex {
-    q: i32,
-    r: i32,
-    s: i32,
+pub struct Hex {
+    q: i64,
+    r: i64,
+    s: i64,
 }
 
 impl Hex {
-    fn origin() -> Self {
+    pub fn origin() -> Self {
         Self::new(0, 0, 0)
     }
 
-    fn new(q: i32, r: i32, s: i32) -> Self {
+    fn new(q: i64, r: i64, s: i64) -> Self {
         Self { q, r, s }
     }
 
-    fn distance(&self) -> i32 {
+    /// The number of steps from the origin to this hex.
+    pub fn distance(&self) -> i64 {
         (self.q.abs() + self.r.abs() + self.s.abs()) / 2
     }
 }
@@ -95,28 +99,61 @@ fn test_hex() {
     assert_eq!(h1 + HexDirection::N, Hex { q: 1, r: 4, s: -3 });
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/11.txt").expect("expected input 11.txt");
+pub struct Day11;
 
-    let directions: Vec<HexDirection> = input.trim().split(",").map(|s| s.parse().unwrap()).collect();
+/// Where the child process ended up; displays as its distance from the origin.
+pub struct Destination(Hex);
 
-    // Part 1
-    {
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.distance())
+    }
+}
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input = Vec<HexDirection>;
+    type Part1 = Destination;
+    type Part2 = i64;
+
+    fn parse(&self, input: &str) -> error::Result<Vec<HexDirection>> {
+        let input = input.trim();
+        input.split(",").map(|s| {
+            s.parse().map_err(|_| AdventError::parse(format!("couldn't parse direction '{}'", s)).at(input, s))
+        }).collect()
+    }
+
+    fn part1(&self, directions: &Vec<HexDirection>) -> error::Result<Destination> {
         let target = directions.iter().fold(Hex::origin(), |acc, &next| acc + next);
-        println!("Part 1: The child process is at {:?}, which is {} units away", target, target.distance());
+        Ok(Destination(target))
     }
 
-    // Part 2
-    {
+    fn part2(&self, directions: &Vec<HexDirection>) -> error::Result<i64> {
         let mut location = Hex::origin();
         let mut max_distance = 0;
-        for dir in directions {
+        for &dir in directions {
             location = location + dir;
             let distance = location.distance();
             if distance > max_distance {
                 max_distance = distance;
             }
         }
-        println!("Part 2: The furthest the child got was {} units away", max_distance);
+        Ok(max_distance)
     }
+
+    fn describe_part1(&self, destination: &Destination) -> String {
+        let Destination(target) = *destination;
+        format!("The child process is at {:?}, which is {} units away", target, target.distance())
+    }
+
+    fn describe_part2(&self, max_distance: &i64) -> String {
+        format!("The furthest the child got was {} units away", max_distance)
+    }
+}
+
+#[test]
+fn test_day11_parse() {
+    assert_eq!(Day11.parse("ne,ne,s\n").unwrap(), vec![HexDirection::NE, HexDirection::NE, HexDirection::S]);
+    assert_eq!(Day11.parse("ne,up,s").unwrap_err().to_string(), "column 4: couldn't parse direction 'up'");
 }
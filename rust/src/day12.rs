@@ -1,8 +1,9 @@
-use common;
+use error::{self, AdventError, Result};
+use solution::Solution;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-struct Village {
+pub struct Village {
     programs: HashMap<u32, HashSet<u32>>,
 }
 
@@ -74,23 +75,52 @@ impl<'a> Searcher<'a> {
     }
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/12.txt").expect("expected input 12.txt");
+pub struct Day12;
 
-    let mut village = Village::new();
-    for line in input.lines() {
-        let idx = line.find(" <-> ").unwrap();
-        let prog: u32 = (&line[..idx]).parse().unwrap();
-        let others: Vec<u32> = (&line[idx+5..]).split(", ").map(|s| s.parse().unwrap()).collect();
-        for o in others {
-            village.connect(prog, o);
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    type Input = Village;
+    type Part1 = usize;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Village> {
+        let mut village = Village::new();
+        let pipes = error::parse_lines(input, |line| {
+            let parse_prog = |s: &str| {
+                s.parse::<u32>().map_err(|_| AdventError::parse(format!("couldn't parse program '{}'", s)).at(line, s))
+            };
+            let idx = line.find(" <-> ").ok_or_else(|| AdventError::parse("expected 'program <-> programs'"))?;
+            let prog = parse_prog(&line[..idx])?;
+            let others = line[idx+5..].split(", ").map(parse_prog).collect::<Result<Vec<u32>>>()?;
+            Ok((prog, others))
+        })?;
+        for (prog, others) in pipes {
+            for o in others {
+                village.connect(prog, o);
+            }
+        }
+        Ok(village)
+    }
+
+    fn part1(&self, village: &Village) -> Result<usize> {
+        if !village.programs.contains_key(&0) {
+            return Err(AdventError::solve("program 0 isn't in the village"));
         }
+        Ok(village.connected_to(0).len())
+    }
+
+    fn part2(&self, village: &Village) -> Result<u32> {
+        Ok(village.num_groups())
+    }
+
+    fn describe_part1(&self, count: &usize) -> String {
+        format!("{} programs in program 0's group", count)
+    }
+
+    fn describe_part2(&self, groups: &u32) -> String {
+        format!("{} groups", groups)
     }
-    let connected = village.connected_to(0);
-    // Part 1
-    println!("Part 1: {} programs in program 0's group", connected.len());
-    // Part 2
-    println!("Part 2: {} groups", village.num_groups());
 }
 
 #[test]
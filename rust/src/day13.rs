@@ -1,6 +1,7 @@
-use common;
+use error::{AdventError, Result};
+use solution::Solution;
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 struct Layer {
     depth: u32,
     range: u32,
@@ -48,20 +49,34 @@ fn test_layer() {
     assert_eq!(l2.pos_at(5), Some(5));
 }
 
-#[derive(Clone)]
-struct Firewall {
+#[derive(Debug, Clone)]
+pub struct Firewall {
     layers: Vec<Layer>,
 }
 
 impl Firewall {
-    fn parse(s: &str) -> Self {
+    fn parse(s: &str) -> Result<Self> {
         let mut layers = Vec::new();
 
         let mut current_layer = 0;
-        for line in s.lines() {
+        for (idx, line) in s.lines().enumerate() {
+            let error = |msg: String, part: &str| AdventError::parse(msg).at(line, part).on_line(idx + 1);
+            let parse_num = |part: &str| {
+                part.parse::<u32>().map_err(|_| error(format!("couldn't parse number '{}'", part), part))
+            };
+
             let parts = line.split(": ").collect::<Vec<_>>();
-            let depth = parts[0].parse::<u32>().unwrap();
-            let range = parts[1].parse::<u32>().unwrap();
+            if parts.len() != 2 {
+                return Err(AdventError::parse("expected 'depth: range'").on_line(idx + 1));
+            }
+            let depth = parse_num(parts[0])?;
+            let range = parse_num(parts[1])?;
+            if depth < current_layer {
+                return Err(error(format!("layer {} is out of order", depth), parts[0]));
+            }
+            if range < 2 {
+                return Err(error("scanner range must be at least 2".to_string(), parts[1]));
+            }
             let layer = Layer::new(depth, range);
 
             for _ in current_layer..depth {
@@ -71,7 +86,7 @@ impl Firewall {
             current_layer = depth + 1;
         }
 
-        Firewall { layers }
+        Ok(Firewall { layers })
     }
 
     fn traverse(&self) -> Option<u32> {
@@ -94,9 +109,14 @@ impl Firewall {
 
 #[test]
 fn test_firewall() {
-    let firewall = Firewall::parse("0: 3\n1: 2\n4: 4\n6: 4");
+    let firewall = Firewall::parse("0: 3\n1: 2\n4: 4\n6: 4").unwrap();
     assert_eq!(firewall.layers.len(), 7);
     assert_eq!(firewall.traverse(), Some(24));
+
+    let err = Firewall::parse("0: 3\n4: 4\n1: 2").unwrap_err();
+    assert_eq!(err.to_string(), "line 3, column 1: layer 1 is out of order");
+    let err = Firewall::parse("0: 3\n1: x").unwrap_err();
+    assert_eq!(err.to_string(), "line 2, column 4: couldn't parse number 'x'");
 }
 
 fn find_safe_delay(firewall: &Firewall) -> Option<u32> {
@@ -105,15 +125,37 @@ fn find_safe_delay(firewall: &Firewall) -> Option<u32> {
 
 #[test]
 fn test_find_safe_delay() {
-    let firewall = Firewall::parse("0: 3\n1: 2\n4: 4\n6: 4");
+    let firewall = Firewall::parse("0: 3\n1: 2\n4: 4\n6: 4").unwrap();
     assert_eq!(find_safe_delay(&firewall), Some(10));
 }
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/13.txt").expect("expected input 13.txt");
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input = Firewall;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Firewall> {
+        Firewall::parse(input.trim())
+    }
+
+    /// A trip where we're never caught has a severity of zero.
+    fn part1(&self, firewall: &Firewall) -> Result<u32> {
+        Ok(firewall.traverse().unwrap_or(0))
+    }
+
+    fn part2(&self, firewall: &Firewall) -> Result<u32> {
+        find_safe_delay(firewall).ok_or_else(|| AdventError::solve("couldn't find a safe delay"))
+    }
+
+    fn describe_part1(&self, severity: &u32) -> String {
+        format!("Severity was {}", severity)
+    }
 
-    let firewall = Firewall::parse(input.trim());
-    println!("Part 1: Severity was {:?}", firewall.traverse());
-    let delay = find_safe_delay(&firewall);
-    println!("Part 2: Delay {:?} picoseconds to get through the firewall", delay);
+    fn describe_part2(&self, delay: &u32) -> String {
+        format!("Delay {} picoseconds to get through the firewall", delay)
+    }
 }
@@ -1,3 +1,6 @@
+use error::{self, AdventError, Result};
+use solution::Solution;
+
 struct FactorGenerator {
     factor: u64,
     div: u64,
@@ -50,39 +53,67 @@ impl Iterator for DivFactorGenerator {
     }
 }
 
-pub fn run(_args: &[String]) {
-    // Hard-coding input
-    let gen_a_start = 277;
-    let gen_a_factor = 16807;
-    let gen_b_start = 349;
-    let gen_b_factor = 48271;
-    let div = 2147483647;
+const GEN_A_FACTOR: u64 = 16807;
+const GEN_B_FACTOR: u64 = 48271;
+const DIV: u64 = 2147483647;
+
+/// The starting values of generators A and B.
+pub struct Starts {
+    a: u64,
+    b: u64,
+}
+
+pub struct Day15;
 
-    {
-        let gen_a = FactorGenerator::new(gen_a_factor, gen_a_start, div);
-        let gen_b = FactorGenerator::new(gen_b_factor, gen_b_start, div);
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    type Input = Starts;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Starts> {
+        let starts: Vec<u64> = error::parse_lines(input.trim(), |line| {
+            let n = line.split_whitespace().last().unwrap_or(line);
+            n.parse().map_err(|_| AdventError::parse("expected a generator's starting value").at(line, n))
+        })?;
+        if starts.len() != 2 {
+            return Err(AdventError::parse("expected starting values for generators A and B"));
+        }
+        Ok(Starts { a: starts[0], b: starts[1] })
+    }
+
+    fn part1(&self, starts: &Starts) -> Result<usize> {
+        let gen_a = FactorGenerator::new(GEN_A_FACTOR, starts.a, DIV);
+        let gen_b = FactorGenerator::new(GEN_B_FACTOR, starts.b, DIV);
 
         // This is sloooowwww
-        let judged = gen_a.zip(gen_b).take(40_000_000).filter(|pair| {
+        Ok(gen_a.zip(gen_b).take(40_000_000).filter(|pair| {
             let &(a, b) = pair;
             let a_bin: String = format!("{:016b}", a).chars().rev().take(16).collect();
             let b_bin: String = format!("{:016b}", b).chars().rev().take(16).collect();
             a_bin == b_bin
-        }).count();
-        println!("Part 1: {} matches", judged);
+        }).count())
     }
 
-    {
-        let gen_a = FactorGenerator::new(gen_a_factor, gen_a_start, div).only_div(4);
-        let gen_b = FactorGenerator::new(gen_b_factor, gen_b_start, div).only_div(8);
+    fn part2(&self, starts: &Starts) -> Result<usize> {
+        let gen_a = FactorGenerator::new(GEN_A_FACTOR, starts.a, DIV).only_div(4);
+        let gen_b = FactorGenerator::new(GEN_B_FACTOR, starts.b, DIV).only_div(8);
 
-        let judged = gen_a.zip(gen_b).take(5_000_000).filter(|pair| {
+        Ok(gen_a.zip(gen_b).take(5_000_000).filter(|pair| {
             let &(a, b) = pair;
             let a_bin: String = format!("{:016b}", a).chars().rev().take(16).collect();
             let b_bin: String = format!("{:016b}", b).chars().rev().take(16).collect();
             a_bin == b_bin
-        }).count();
-        println!("Part 2: {} matches", judged);
+        }).count())
+    }
+
+    fn describe_part1(&self, judged: &usize) -> String {
+        format!("{} matches", judged)
+    }
+
+    fn describe_part2(&self, judged: &usize) -> String {
+        format!("{} matches", judged)
     }
 }
 
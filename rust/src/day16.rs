@@ -1,87 +1,374 @@
-use common;
+use error::{AdventError, Result};
+use solution::Solution;
+use std::fmt;
 use self::DanceMove::*;
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/16.txt").expect("expected input 16.txt");
-    let mut programs: Vec<char> = "abcdefghijklmnop".chars().collect();
-    let moves: Vec<&str> = input.trim().split(",").collect();
-    dance(&mut programs, &moves);
-    println!("Part 1: order is {}", programs.iter().collect::<String>());
-    let mut loop_size = 0;
-    for i in 1..1_000_000_000 {
-        dance(&mut programs, &moves);
-        if programs.iter().collect::<String>() == "abcdefghijklmnop" {
-            loop_size = i + 1;
-            break;
+/// The dance, performed by the programs in `lineup`. By default,
+/// that's sixteen programs named `a` to `p`.
+pub struct Day16 {
+    lineup: Vec<char>,
+}
+
+impl Day16 {
+    /// Dancers with the given names, standing in the given order.
+    pub fn with_lineup(lineup: &str) -> Result<Self> {
+        let lineup: Vec<char> = lineup.chars().collect();
+        if lineup.is_empty() {
+            return Err(AdventError::parse("the lineup needs at least one program"));
+        }
+        for (idx, program) in lineup.iter().enumerate() {
+            if lineup[..idx].contains(program) {
+                return Err(AdventError::parse(format!("program {} is in the lineup twice", program)).at_column(idx + 1));
+            }
         }
+        Ok(Day16 { lineup })
     }
-    let remainder = 1_000_000_000 % loop_size;
-    for _ in 0..remainder {
-        dance(&mut programs, &moves);
+
+    /// The first `count` programs, in alphabetical order.
+    pub fn with_dancers(count: usize) -> Result<Self> {
+        if count == 0 || count > 26 {
+            return Err(AdventError::parse(format!("can't have {} dancers; there must be between 1 and 26", count)));
+        }
+        Ok(Day16 { lineup: (b'a'..b'a' + count as u8).map(char::from).collect() })
+    }
+}
+
+impl Default for Day16 {
+    fn default() -> Self {
+        Day16 { lineup: LINEUP.chars().collect() }
+    }
+}
+
+/// The final order of the programs, along with the loop size: the fewest
+/// dances after which the programs stand in their starting order again.
+/// The dance itself may take longer to come back around, if its moves and
+/// partner swaps only cancel out in the lineup they're applied to.
+pub struct Order {
+    order: String,
+    loop_size: usize,
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.order)
     }
-    println!("Part 2: order is {} with a loop size of {}", programs.iter().collect::<String>(), loop_size);
 }
 
-#[derive(Debug)]
-enum DanceMove {
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    type Input = Vec<DanceMove>;
+    type Part1 = String;
+    type Part2 = Order;
+
+    fn parse(&self, input: &str) -> Result<Vec<DanceMove>> {
+        let input = input.trim();
+        input.split(",").enumerate().map(|(idx, s)| {
+            parse_move(s)
+                .and_then(|dance_move| check_move(&dance_move, &self.lineup).map(|_| dance_move))
+                .map_err(|err| err.at(input, s).context(format!("move {}", idx + 1)))
+        }).collect()
+    }
+
+    fn part1(&self, moves: &Vec<DanceMove>) -> Result<String> {
+        let dance = Dance::new(moves, &self.lineup);
+        Ok(dance.perform(&self.lineup).iter().collect())
+    }
+
+    fn part2(&self, moves: &Vec<DanceMove>) -> Result<Order> {
+        let dance = Dance::new(moves, &self.lineup);
+        let order = dance.pow(1_000_000_000).perform(&self.lineup);
+        Ok(Order { order: order.iter().collect(), loop_size: dance.loop_size() as usize })
+    }
+
+    fn describe_part1(&self, order: &String) -> String {
+        format!("order is {}", order)
+    }
+
+    fn describe_part2(&self, answer: &Order) -> String {
+        format!("order is {} with a loop size of {}", answer.order, answer.loop_size)
+    }
+}
+
+/// The programs, in the order they usually start the dance.
+const LINEUP: &str = "abcdefghijklmnop";
+
+#[derive(Debug, PartialEq)]
+pub enum DanceMove {
     Spin(u32),
     Exchange(usize, usize),
     Partner(char, char),
 }
 
-fn parse_move(dance_move: &&str) -> Option<DanceMove> {
-    let first = dance_move.chars().next();
-    let rest: String = dance_move.chars().skip(1).collect();
+/// Parses a single move like `s1`, `x3/4` or `pe/b`. Error columns
+/// are relative to the start of the move.
+fn parse_move(dance_move: &str) -> Result<DanceMove> {
+    let mut chars = dance_move.chars();
+    let first = chars.next();
+    let rest = chars.as_str();
+    let pair = || -> Result<(&str, &str)> {
+        let idx = rest.find('/').ok_or_else(|| AdventError::parse("expected a pair like a/b").at_column(2))?;
+        Ok((&rest[..idx], &rest[idx+1..]))
+    };
+    let position = |s: &str| {
+        s.parse::<usize>().map_err(|_| AdventError::parse(format!("couldn't parse position '{}'", s)).at(dance_move, s))
+    };
+    let program = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(AdventError::parse(format!("expected a single program, got '{}'", s)).at(dance_move, s)),
+        }
+    };
 
     match first {
         Some('s') => {
-            let num: u32 = rest.parse().unwrap();
-            Some(Spin(num))
+            let num: u32 = rest.parse().map_err(|_| {
+                AdventError::parse(format!("couldn't parse spin size '{}'", rest)).at_column(2)
+            })?;
+            Ok(Spin(num))
         },
         Some('x') => {
-            let parts: Vec<usize> = rest.split("/").map(|s| s.parse().unwrap()).collect();
-            Some(Exchange(parts[0], parts[1]))
+            let (a, b) = pair()?;
+            Ok(Exchange(position(a)?, position(b)?))
         },
         Some('p') => {
-            let mut programs = rest.split("/");
-            let first: char = programs.next().unwrap().chars().next().unwrap();
-            let second: char = programs.next().unwrap().chars().next().unwrap();
-            Some(Partner(first, second))
+            let (a, b) = pair()?;
+            Ok(Partner(program(a)?, program(b)?))
+        }
+        _ => Err(AdventError::parse(format!("unknown dance move '{}'", dance_move)).at_column(1))
+    }
+}
+
+/// Makes sure a move can be danced by the programs in `lineup`.
+fn check_move(dance_move: &DanceMove, lineup: &[char]) -> Result<()> {
+    let size = lineup.len();
+    match *dance_move {
+        Exchange(pos1, pos2) if pos1 >= size || pos2 >= size => {
+            Err(AdventError::parse(format!("can't exchange positions {} and {} in a line of {}", pos1, pos2, size)))
+        },
+        Partner(prog1, prog2) if !lineup.contains(&prog1) || !lineup.contains(&prog2) => {
+            Err(AdventError::parse(format!("can't partner {} and {}; there's no such program", prog1, prog2)))
+        },
+        _ => Ok(())
+    }
+}
+
+/// A rearrangement of a line of things, as the position each thing comes
+/// from: rearranging `items` gives `[items[p[0]], items[p[1]], ...]`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Permutation(Vec<usize>);
+
+impl Permutation {
+    pub fn identity(size: usize) -> Self {
+        Permutation((0..size).collect())
+    }
+
+    /// The rearrangement made by doing this one, then `other`.
+    pub fn then(&self, other: &Permutation) -> Self {
+        Permutation(other.0.iter().map(|&idx| self.0[idx]).collect())
+    }
+
+    /// This rearrangement done `n` times over, by repeated squaring.
+    pub fn pow(&self, mut n: u64) -> Self {
+        let mut result = Permutation::identity(self.0.len());
+        let mut square = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.then(&square);
+            }
+            square = square.then(&square);
+            n >>= 1;
         }
-        _ => None
-    }
-}
-
-fn dance(programs: &mut Vec<char>, moves: &[&str]) {
-    let steps = moves.iter().map(parse_move);
-
-    for step in steps {
-        match step {
-            Some(Spin(amount)) => {
-                for _ in 0..amount {
-                    let program = programs.pop().unwrap();
-                    programs.insert(0, program);
-                }
-            },
-            Some(Exchange(pos1, pos2)) => {
-                programs.swap(pos1, pos2);
-            },
-            Some(Partner(prog1, prog2)) => {
-                let pos1 = programs.iter().position(|&p| p == prog1).expect("couldn't find program");
-                let pos2 = programs.iter().position(|&p| p == prog2).expect("couldn't find program");
-                programs.swap(pos1, pos2);
-            },
-            None => {
-                panic!("No dance move found")
+        result
+    }
+
+    pub fn apply<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        self.0.iter().map(|&idx| items[idx].clone()).collect()
+    }
+
+    /// How many times this rearrangement has to be done before everything
+    /// is back where it started: the lcm of the lengths of its cycles.
+    pub fn order(&self) -> u64 {
+        let mut seen = vec![false; self.0.len()];
+        let mut order = 1;
+        for start in 0..self.0.len() {
+            let mut length = 0;
+            let mut idx = start;
+            while !seen[idx] {
+                seen[idx] = true;
+                idx = self.0[idx];
+                length += 1;
+            }
+            if length > 0 {
+                order = order / gcd(order, length) * length;
             }
         }
+        order
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// The overall effect of a sequence of dance moves. Spins and exchanges
+/// move programs around regardless of their names, and partner swaps
+/// rename programs regardless of where they stand, so the two kinds of
+/// move can be gathered into separate permutations that don't interfere:
+/// one of positions, and one of names (as indexes into the starting lineup).
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Dance {
+    positions: Permutation,
+    names: Permutation,
+}
+
+impl Dance {
+    /// A dance in which nobody moves.
+    pub fn identity(size: usize) -> Self {
+        Dance { positions: Permutation::identity(size), names: Permutation::identity(size) }
+    }
+
+    /// Gathers up the moves for the given starting lineup. The moves must
+    /// already have been checked to make sense for it.
+    pub fn new(moves: &[DanceMove], lineup: &[char]) -> Self {
+        let size = lineup.len();
+        let name = |program| lineup.iter().position(|&p| p == program).expect("couldn't find program");
+        let mut positions: Vec<usize> = (0..size).collect();
+        // Which name each name has been swapped to
+        let mut names: Vec<usize> = (0..size).collect();
+
+        for step in moves {
+            match *step {
+                Spin(amount) => {
+                    let amount = amount as usize % size;
+                    positions.rotate_right(amount);
+                },
+                Exchange(pos1, pos2) => {
+                    positions.swap(pos1, pos2);
+                },
+                Partner(prog1, prog2) => {
+                    let (name1, name2) = (name(prog1), name(prog2));
+                    for name in names.iter_mut() {
+                        if *name == name1 {
+                            *name = name2;
+                        } else if *name == name2 {
+                            *name = name1;
+                        }
+                    }
+                },
+            }
+        }
+
+        Dance { positions: Permutation(positions), names: Permutation(names) }
+    }
+
+    /// This dance followed by `other`. Renamings apply to the names the
+    /// programs already have, so they compose the other way around.
+    pub fn then(&self, other: &Dance) -> Self {
+        Dance { positions: self.positions.then(&other.positions), names: other.names.then(&self.names) }
+    }
+
+    /// This dance performed `n` times in a row.
+    pub fn pow(&self, n: u64) -> Self {
+        Dance { positions: self.positions.pow(n), names: self.names.pow(n) }
+    }
+
+    /// The order the programs of `lineup` end up in after the dance.
+    pub fn perform(&self, lineup: &[char]) -> Vec<char> {
+        self.names.then(&self.positions).apply(lineup)
+    }
+
+    /// The fewest times the dance has to be performed for the programs to
+    /// be back in their starting order.
+    ///
+    /// After `period` dances, the lcm of the orders of the two permutations,
+    /// both are back to the identity, so the lineup is too. And once the
+    /// lineup comes back after `n` dances, every `n` dances after that just
+    /// repeat the same orders, so the loop size divides `period` and only
+    /// its divisors need checking.
+    pub fn loop_size(&self) -> u64 {
+        let (names, positions) = (self.names.order(), self.positions.order());
+        let period = names / gcd(names, positions) * positions;
+        let identity = Permutation::identity(self.names.0.len());
+        (1..period + 1)
+            .filter(|&n| period.is_multiple_of(n))
+            .find(|&n| {
+                let dance = self.pow(n);
+                dance.names.then(&dance.positions) == identity
+            })
+            .unwrap_or(period)
     }
 }
 
 #[test]
 fn test_dance() {
-    let mut programs = vec!['a', 'b', 'c', 'd', 'e'];
-    let moves = vec!["s1", "x3/4", "pe/b"];
-    dance(&mut programs, &moves);
-    assert_eq!(programs, vec!['b', 'a', 'e', 'd', 'c']);
+    let programs = vec!['a', 'b', 'c', 'd', 'e'];
+    let moves: Vec<DanceMove> = ["s1", "x3/4", "pe/b"].iter().map(|m| parse_move(m).unwrap()).collect();
+    let dance = Dance::new(&moves, &programs);
+    assert_eq!(dance.perform(&programs), vec!['b', 'a', 'e', 'd', 'c']);
+    assert_eq!(dance.then(&dance).perform(&programs), vec!['c', 'e', 'a', 'd', 'b']);
+    assert_eq!(dance.pow(2), dance.then(&dance));
+    assert_eq!(dance.pow(0), Dance::identity(5));
+
+    let mut repeated = Dance::identity(5);
+    for _ in 0..13 {
+        repeated = repeated.then(&dance);
+    }
+    assert_eq!(dance.pow(13).perform(&programs), repeated.perform(&programs));
+}
+
+#[test]
+fn test_permutation() {
+    let rotate = Permutation(vec![2, 0, 1]);
+    assert_eq!(rotate.apply(&['a', 'b', 'c']), vec!['c', 'a', 'b']);
+    assert_eq!(rotate.then(&rotate).apply(&['a', 'b', 'c']), vec!['b', 'c', 'a']);
+    assert_eq!(rotate.pow(3), Permutation::identity(3));
+    assert_eq!(rotate.pow(1_000_000_000), rotate);
+    assert_eq!(rotate.order(), 3);
+    assert_eq!(Permutation(vec![1, 0, 3, 4, 2]).order(), 6);
+    assert_eq!(Permutation::identity(4).order(), 1);
+}
+
+#[test]
+fn test_parse_move() {
+    assert_eq!(parse_move("x3/4").unwrap(), Exchange(3, 4));
+    assert_eq!(parse_move("x3/q").unwrap_err().to_string(), "column 4: couldn't parse position 'q'");
+    let day = Day16::default();
+    assert_eq!(day.parse("s1,pe/b,y2").unwrap_err().to_string(), "column 9: move 3: unknown dance move 'y2'");
+    assert_eq!(day.parse("s1,x3/16").unwrap_err().to_string(), "column 4: move 2: can't exchange positions 3 and 16 in a line of 16");
+    assert_eq!(day.parse("s1,pa/q").unwrap_err().to_string(), "column 4: move 2: can't partner a and q; there's no such program");
+}
+
+#[test]
+fn test_lineup() {
+    let day = Day16::with_dancers(5).unwrap();
+    let moves = day.parse("s1,x3/4,pe/b").unwrap();
+    assert_eq!(day.part1(&moves).unwrap(), "baedc");
+    assert_eq!(day.parse("x3/5").unwrap_err().to_string(), "column 1: move 1: can't exchange positions 3 and 5 in a line of 5");
+    assert!(day.parse("pe/f").is_err());
+
+    let order = day.part2(&moves).unwrap();
+    let dance = Dance::new(&moves, &['a', 'b', 'c', 'd', 'e']);
+    let mut repeated = dance.clone();
+    let mut loop_size = 1;
+    while repeated.perform(&day.lineup) != day.lineup {
+        repeated = repeated.then(&dance);
+        loop_size += 1;
+    }
+    assert_eq!((order.order.as_str(), order.loop_size), ("abcde", loop_size));
+
+    // The spin and the partner swap cancel out in the lineup, but not in the dance
+    let pair = Day16::with_dancers(2).unwrap();
+    let moves = pair.parse("s1,pa/b").unwrap();
+    assert_eq!(pair.part2(&moves).unwrap().loop_size, 1);
+    assert_ne!(Dance::new(&moves, &['a', 'b']), Dance::identity(2));
+
+    let day = Day16::with_lineup("edcba").unwrap();
+    let moves = day.parse("s1,x3/4,pe/b").unwrap();
+    assert_eq!(day.part1(&moves).unwrap(), "abdec");
+
+    assert!(Day16::with_dancers(0).is_err());
+    assert!(Day16::with_dancers(27).is_err());
+    assert_eq!(Day16::with_lineup("abca").err().unwrap().to_string(), "column 4: program a is in the lineup twice");
 }
@@ -1,14 +1,38 @@
-pub fn run(_args: &[String]) {
-    // Hard-coded input
-    let mut v = Vec::with_capacity(50_000_001);
-    v.push(0);
-    spinlock(&mut v, 349, 1, 2017);
-    let idx = v.iter().position(|&n| n == 2017).expect("couldn't find position of 2017");
-    let n = v[idx+1];
-    println!("Part 1: The number after 2017 is {}", n);
-
-    let m = spinlock_for_index(1, 349, 1, 50_000_000);
-    println!("Part 1: The number after 0 is {:?}", m);
+use error::{AdventError, Result};
+use solution::Solution;
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+
+    type Input = usize;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(&self, input: &str) -> Result<usize> {
+        input.trim().parse().map_err(|_| AdventError::parse("couldn't parse input as a number"))
+    }
+
+    fn part1(&self, &steps: &usize) -> Result<u64> {
+        let mut v = Vec::with_capacity(2018);
+        v.push(0);
+        spinlock(&mut v, steps, 1, 2017);
+        let idx = v.iter().position(|&n| n == 2017).ok_or_else(|| AdventError::solve("2017 was never inserted"))?;
+        Ok(v[(idx + 1) % v.len()])
+    }
+
+    fn part2(&self, &steps: &usize) -> Result<u64> {
+        spinlock_for_index(1, steps, 1, 50_000_000).ok_or_else(|| AdventError::solve("nothing was ever inserted after 0"))
+    }
+
+    fn describe_part1(&self, n: &u64) -> String {
+        format!("The number after 2017 is {}", n)
+    }
+
+    fn describe_part2(&self, m: &u64) -> String {
+        format!("The number after 0 is {}", m)
+    }
 }
 
 fn spinlock(v: &mut Vec<u64>, steps: usize, start: u64, end: u64) {
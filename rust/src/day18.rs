@@ -1,22 +1,30 @@
-use common;
+use error::{self, AdventError, Result};
+use solution::Solution;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/18.txt").expect("expected input 18.txt");
-    let instructions: Vec<Instruction> = input.trim().lines().map(|line| {
-        Instruction::parse(line).expect("expected to parse an instruction from a line")
-    }).collect();
+pub struct Day18;
 
-    {
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    type Input = Vec<Instruction>;
+    type Part1 = i64;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>> {
+        error::parse_lines(input.trim(), Instruction::parse)
+    }
+
+    fn part1(&self, instructions: &Vec<Instruction>) -> Result<i64> {
         let mut machine = Machine::new();
-        machine.run(&instructions);
-        println!("Part 1: {:?}", machine.sound);
+        machine.run(instructions);
+        machine.sound.ok_or_else(|| AdventError::solve("no sound was played"))
     }
 
-    {
-        let mut prog0 = Program::new(0, &instructions);
-        let mut prog1 = Program::new(1, &instructions);
+    fn part2(&self, instructions: &Vec<Instruction>) -> Result<usize> {
+        let mut prog0 = Program::new(0, instructions);
+        let mut prog1 = Program::new(1, instructions);
 
         loop {
             let deadlock = prog0.is_waiting() && prog1.is_waiting();
@@ -30,18 +38,18 @@ pub fn run(_args: &[String]) {
             prog1.exec_next(&mut prog0);
         }
 
-        println!("Part 2: {}", prog1.get_send_count());
+        Ok(prog1.get_send_count())
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-enum RegisterValue {
+pub enum RegisterValue {
     Register(String),
     Value(i64),
 }
 
 impl RegisterValue {
-    fn parse(val: &str) -> Self {
+    pub fn parse(val: &str) -> Self {
         match val.parse::<i64>() {
             Ok(num) => RegisterValue::Value(num),
             _       => RegisterValue::Register(val.to_string()),
@@ -50,7 +58,7 @@ impl RegisterValue {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-enum Instruction {
+pub enum Instruction {
     Snd(RegisterValue),
     Set(String, RegisterValue),
     Add(String, RegisterValue),
@@ -61,46 +69,48 @@ enum Instruction {
 }
 
 impl Instruction {
-    fn parse(instr: &str) -> Option<Self> {
+    pub fn parse(instr: &str) -> Result<Self> {
         let mut parts = instr.split(" ");
+        let mut next = |what| error::next_token(&mut parts, instr, what);
 
-        match parts.next().unwrap() {
+        let name = next("an instruction")?;
+        match name {
             "snd" => {
-                let val = parts.next().unwrap();
-                Some(Instruction::Snd(RegisterValue::parse(val)))
+                let val = next("a value")?;
+                Ok(Instruction::Snd(RegisterValue::parse(val)))
             },
             "set" => {
-                let reg = parts.next().unwrap();
-                let val = parts.next().unwrap();
-                Some(Instruction::Set(reg.to_string(), RegisterValue::parse(val)))
+                let reg = next("a register")?;
+                let val = next("a value")?;
+                Ok(Instruction::Set(reg.to_string(), RegisterValue::parse(val)))
             },
             "add" => {
-                let reg = parts.next().unwrap();
-                let val = parts.next().unwrap();
-                Some(Instruction::Add(reg.to_string(), RegisterValue::parse(val)))
+                let reg = next("a register")?;
+                let val = next("a value")?;
+                Ok(Instruction::Add(reg.to_string(), RegisterValue::parse(val)))
             },
             "mul" => {
-                let reg = parts.next().unwrap();
-                let val = parts.next().unwrap();
-                Some(Instruction::Mul(reg.to_string(), RegisterValue::parse(val)))
+                let reg = next("a register")?;
+                let val = next("a value")?;
+                Ok(Instruction::Mul(reg.to_string(), RegisterValue::parse(val)))
             },
             "mod" => {
-                let reg = parts.next().unwrap();
-                let val = parts.next().unwrap();
-                Some(Instruction::Mod(reg.to_string(), RegisterValue::parse(val)))
+                let reg = next("a register")?;
+                let val = next("a value")?;
+                Ok(Instruction::Mod(reg.to_string(), RegisterValue::parse(val)))
             },
             "rcv" => {
-                Some(Instruction::Rcv(parts.next().unwrap().to_string()))
+                Ok(Instruction::Rcv(next("a register")?.to_string()))
             },
             "jgz" => {
-                let val1 = parts.next().unwrap();
-                let val2 = parts.next().unwrap();
-                Some(Instruction::Jmp(
+                let val1 = next("a value")?;
+                let val2 = next("an offset")?;
+                Ok(Instruction::Jmp(
                     RegisterValue::parse(val1),
                     RegisterValue::parse(val2),
                 ))
             }
-            _ => None
+            _ => Err(AdventError::parse(format!("unknown instruction '{}'", name)).at(instr, name))
         }
     }
 }
@@ -110,26 +120,33 @@ fn test_parse_soundcard_instruction() {
     use self::Instruction::*;
     use self::RegisterValue::*;
 
-    assert_eq!(Instruction::parse("snd 14"), Some(Snd(Value(14))));
-    assert_eq!(Instruction::parse("snd x"), Some(Snd(Register("x".to_string()))));
+    assert_eq!(Instruction::parse("snd 14").unwrap(), Snd(Value(14)));
+    assert_eq!(Instruction::parse("snd x").unwrap(), Snd(Register("x".to_string())));
+
+    assert_eq!(Instruction::parse("set a 13").unwrap(),
+               Set("a".to_string(), Value(13)));
+    assert_eq!(Instruction::parse("set a x").unwrap(),
+               Set("a".to_string(), Register("x".to_string())));
 
-    assert_eq!(Instruction::parse("set a 13"),
-               Some(Set("a".to_string(), Value(13))));
-    assert_eq!(Instruction::parse("set a x"),
-               Some(Set("a".to_string(), Register("x".to_string()))));
+    assert_eq!(Instruction::parse("set a").unwrap_err().to_string(), "column 6: expected a value");
+    assert_eq!(Instruction::parse("jmp a 2").unwrap_err().to_string(), "column 1: unknown instruction 'jmp'");
+    assert_eq!(Instruction::parse("sub b -17").unwrap_err().to_string(), "column 1: unknown instruction 'sub'");
+    assert_eq!(Instruction::parse("jnz 1 3").unwrap_err().to_string(), "column 1: unknown instruction 'jnz'");
 }
 
-struct Machine {
+/// A sound card that runs Duet instructions, treating `snd` as playing a
+/// sound and stopping at the first `rcv` of a non-zero register.
+pub struct Machine {
     registers: HashMap<String, i64>,
     sound: Option<i64>,
 }
 
 impl Machine {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self { registers: HashMap::new(), sound: None }
     }
 
-    fn run(&mut self, instructions: &Vec<Instruction>) {
+    pub fn run(&mut self, instructions: &Vec<Instruction>) {
         use self::Instruction::*;
         let mut pointer: i64 = 0;
 
@@ -182,28 +199,37 @@ impl Machine {
         }
     }
 
-    fn resolve_value(&self, register_or_val: &RegisterValue) -> i64 {
+    pub fn resolve_value(&self, register_or_val: &RegisterValue) -> i64 {
         match register_or_val {
             &RegisterValue::Register(ref reg) => *self.registers.get(reg).unwrap_or(&0),
             &RegisterValue::Value(val)        => val
         }
     }
 
-    fn get_register(&self, register: &String) -> i64 {
+    /// The most recently played sound, if any.
+    pub fn sound(&self) -> Option<i64> {
+        self.sound
+    }
+
+    pub fn get_register(&self, register: &String) -> i64 {
         *self.registers.get(register).unwrap_or(&0)
     }
 
-    fn set_register(&mut self, register: &String, value: i64) {
+    pub fn set_register(&mut self, register: &String, value: i64) {
         self.registers.insert(register.clone(), value);
     }
 }
 
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[test]
 fn test_machine() {
     let input = "set a 1\nadd a 2\nmul a a\nmod a 5\nsnd a\nset a 0\nrcv a\njgz a -1\nset a 1\njgz a -2";
-    let instructions: Vec<Instruction> = input.trim().lines().map(|line| {
-        Instruction::parse(line).expect("expected to parse an instruction from a line")
-    }).collect();
+    let instructions = Day18.parse(input).unwrap();
     let mut machine = Machine::new();
     machine.run(&instructions);
     assert_eq!(machine.sound, Some(4));
@@ -1,204 +1,144 @@
-use common;
-use std::collections::HashMap;
-
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/19.txt").expect("expected input 19.txt");
-    let maze = Maze::from_map(&input);
-    let treasure = maze.find_treasure().iter().collect::<String>();
-    println!("Part 1: {}", treasure);
-    println!("Part 2: {}", maze.iter().count());
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-enum Direction { N, S, E, W }
-
-impl Direction {
-    fn opposite(&self) -> Direction {
-        match self {
-            &Direction::N => Direction::S,
-            &Direction::S => Direction::N,
-            &Direction::E => Direction::W,
-            &Direction::W => Direction::E,
-        }
-    }
-}
+use coords::{Direction4, Grid, Point};
+use error::{AdventError, Result};
+use solution::Solution;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Cell(i64, i64);
-
-/// Maze coordinates start at (0, 0) for the upper-left.
-/// `x` increases to the right, `y` increases downward.
-impl Cell {
-    pub fn neighbor(&self, dir: Direction) -> Self {
-        use self::Direction::*;
-        match dir {
-            N => self.north(),
-            S => self.south(),
-            E => self.east(),
-            W => self.west(),
-        }
-    }
+pub struct Day19;
 
-    fn north(&self) -> Self {
-        Cell(self.0, self.1 - 1)
-    }
+impl Solution for Day19 {
+    const DAY: u32 = 19;
 
-    fn south(&self) -> Self {
-        Cell(self.0, self.1 + 1)
-    }
+    type Input = Maze;
+    type Part1 = String;
+    type Part2 = usize;
 
-    fn east(&self) -> Self {
-        Cell(self.0 + 1, self.1)
+    fn parse(&self, input: &str) -> Result<Maze> {
+        let maze = Maze::from_map(input)?;
+        if maze.get_start().is_none() {
+            return Err(AdventError::parse("couldn't find the start of the path on the first line"));
+        }
+        Ok(maze)
     }
 
-    fn west(&self) -> Self {
-        Cell(self.0 - 1, self.1)
+    fn part1(&self, maze: &Maze) -> Result<String> {
+        Ok(maze.find_treasure()?.iter().collect())
     }
 
-    fn direction_from(&self, previous: Cell) -> Option<Direction> {
-        let delta_x = previous.0 - self.0;
-        let delta_y = previous.1 - self.1;
-
-        match (delta_x, delta_y) {
-            ( 0,  1) => Some(Direction::N),
-            ( 0, -1) => Some(Direction::S),
-            ( 1,  0) => Some(Direction::W),
-            (-1,  0) => Some(Direction::E),
-            _        => None,
-        }
+    fn part2(&self, maze: &Maze) -> Result<usize> {
+        maze.iter().try_fold(0, |steps, cell| cell.map(|_| steps + 1))
     }
 }
 
-#[test]
-fn test_cell_neighbors() {
-    let cell = Cell(4, -3);
-    assert_eq!(cell.north(), Cell(4, -4));
-    assert_eq!(cell.south(), Cell(4, -2));
-    assert_eq!(cell.east(),  Cell(5, -3));
-    assert_eq!(cell.west(),  Cell(3, -3));
-}
-
-#[test]
-fn test_cell_direction_from() {
-    let cell = Cell(5, 5);
-    assert_eq!(cell.direction_from(Cell(4, 5)), Some(Direction::E));
-    assert_eq!(cell.direction_from(Cell(6, 5)), Some(Direction::W));
-    assert_eq!(cell.direction_from(Cell(5, 4)), Some(Direction::S));
-    assert_eq!(cell.direction_from(Cell(5, 6)), Some(Direction::N));
-}
-
 #[derive(Debug, PartialEq, Eq)]
-enum RoomType { NS, EW, Corner, Letter(char), Empty }
+enum RoomType { NS, EW, Corner, Letter(char) }
 
-struct Maze {
-    rooms: HashMap<Cell, RoomType>,
+/// The routing diagram. Spaces aren't stored, so any cell without
+/// a room is off the path. Maze coordinates start at (0, 0) for the
+/// upper-left; `x` increases to the right, `y` increases downward.
+pub struct Maze {
+    rooms: Grid<RoomType>,
 }
 
 impl Maze {
-    fn from_map(map: &str) -> Self {
-        let mut rooms = HashMap::new();
-
-        for (line_idx, line) in map.trim_right_matches("\n").lines().enumerate() {
-            for (char_idx, ch) in line.chars().enumerate() {
-                let cell = Cell(char_idx as i64, line_idx as i64);
-                let room = match ch {
-                    '|' => RoomType::NS,
-                    '-' => RoomType::EW,
-                    '+' => RoomType::Corner,
-                    ' ' => RoomType::Empty,
-                    _   => RoomType::Letter(ch),
-                };
-                rooms.insert(cell, room);
-            }
-        }
-
-        Self { rooms }
+    fn from_map(map: &str) -> Result<Self> {
+        let rooms = Grid::parse_sparse(map, |ch| match ch {
+            '|' => Ok(Some(RoomType::NS)),
+            '-' => Ok(Some(RoomType::EW)),
+            '+' => Ok(Some(RoomType::Corner)),
+            ' ' => Ok(None),
+            _ if ch.is_alphabetic() => Ok(Some(RoomType::Letter(ch))),
+            _   => Err(AdventError::parse(format!("unexpected character '{}' in the diagram", ch))),
+        })?;
+
+        Ok(Self { rooms })
     }
 
-    fn get_start(&self) -> Option<Cell> {
+    fn get_start(&self) -> Option<Point> {
         let start_pair = self.rooms.iter().find(|&(cell, room)| {
             *room == RoomType::NS && cell.1 == 0
         });
 
-        start_pair.map(|pair| *pair.0)
+        start_pair.map(|pair| pair.0)
     }
 
-    fn get_room(&self, cell: &Cell) -> Option<&RoomType> {
-        self.rooms.get(cell)
+    fn get_room(&self, cell: &Point) -> Option<&RoomType> {
+        self.rooms.get(*cell)
     }
 
     fn iter(&self) -> MazeIter {
         MazeIter::new(self)
     }
 
-    fn find_treasure(&self) -> Vec<char> {
+    fn find_treasure(&self) -> Result<Vec<char>> {
         let mut treasure = vec![];
         for cell in self.iter() {
-            let room = self.get_room(&cell).expect("Expected iter'd room to exist");
-            if let &RoomType::Letter(ch) = room {
+            if let Some(&RoomType::Letter(ch)) = self.get_room(&cell?) {
                 treasure.push(ch);
             }
         }
 
-        treasure
+        Ok(treasure)
     }
 }
 
+/// Follows the path through the maze, one cell at a time. A diagram
+/// whose path can't be followed, because a corner doesn't lead exactly
+/// one way on, ends the walk with an error pointing at that corner.
 struct MazeIter<'a> {
     maze: &'a Maze,
-    last_cell: Option<Cell>,
-    direction: Direction,
+    last_cell: Option<Point>,
+    direction: Direction4,
+    failed: bool,
 }
 
 impl<'a> MazeIter<'a> {
     fn new(maze: &'a Maze) -> Self {
-        Self { maze, last_cell: None, direction: Direction::S }
+        Self { maze, last_cell: None, direction: Direction4::South, failed: false }
     }
 
-    fn cell_after(&self, cell: Cell) -> Option<Cell> {
+    fn cell_after(&self, cell: Point) -> Result<Option<Point>> {
         use self::RoomType::*;
 
-        let room = self.maze.get_room(&cell).expect("No room found for visited cell!");
+        let error = |message: &str| AdventError::solve(message).on_line(cell.1 as usize + 1).at_column(cell.0 as usize + 1);
+        let room = self.maze.get_room(&cell).ok_or_else(|| error("the path left the diagram"))?;
         match room {
             &NS | &EW | &Letter(_) => {
-                let next = cell.neighbor(self.direction);
-                if let Some(next_room) = self.maze.get_room(&next) {
-                    if *next_room == RoomType::Empty {
-                        None
-                    } else {
-                        Some(next)
-                    }
-                } else {
-                    None
-                }
+                let next = cell + self.direction;
+                Ok(self.maze.get_room(&next).map(|_| next))
             },
             &Corner => {
-                let candidate_neighbors = vec![cell.north(), cell.south(), cell.east(), cell.west()];
+                let candidate_neighbors = cell.neighbors4();
+                let previous = cell + self.direction.opposite();
                 let remaining = candidate_neighbors.iter().filter(|&&c| {
-                    if let Some(room) = self.maze.get_room(&c) {
-                        let previous = cell.neighbor(self.direction.opposite());
-                        c != previous && *room != RoomType::Empty
-                    } else {
-                        false
-                    }
+                    c != previous && self.maze.get_room(&c).is_some()
                 }).collect::<Vec<_>>();
-                assert!(remaining.len() == 1);
-                Some(*remaining[0])
-            },
-            &Empty => {
-                panic!("Shouldn't be able to visit Empty room");
+                match remaining.len() {
+                    1 => Ok(Some(*remaining[0])),
+                    0 => Err(error("the path ends at a corner")),
+                    _ => Err(error("the path splits at a corner")),
+                }
             },
         }
     }
 }
 
 impl<'a> Iterator for MazeIter<'a> {
-    type Item = Cell;
+    type Item = Result<Point>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None
+        }
+
         let next_cell = match self.last_cell {
             Some(cell) => self.cell_after(cell),
-            None       => self.maze.get_start(),
+            None       => Ok(self.maze.get_start()),
+        };
+        let next_cell = match next_cell {
+            Ok(next_cell) => next_cell,
+            Err(err)      => {
+                self.failed = true;
+                return Some(Err(err));
+            },
         };
 
         if next_cell.is_none() {
@@ -206,31 +146,46 @@ impl<'a> Iterator for MazeIter<'a> {
         }
 
         let next_direction = match self.last_cell {
-            Some(cell) => next_cell.unwrap().direction_from(cell).unwrap(),
-            None       => Direction::S,
+            Some(cell) => Direction4::from_delta(next_cell.unwrap() - cell).unwrap(),
+            None       => Direction4::South,
         };
 
         self.last_cell = next_cell;
         self.direction = next_direction;
 
-        next_cell
+        next_cell.map(Ok)
     }
 }
 
 #[test]
 fn test_maze() {
     let input = "     |          \n     |  +--+    \n     A  |  C    \n F---|----E|--+ \n     |  |  |  D \n     +B-+  +--+ \n";
-    let maze = Maze::from_map(input);
-    assert_eq!(maze.get_start(), Some(Cell(5, 0)));
+    let maze = Maze::from_map(input).unwrap();
+    assert_eq!(maze.get_start(), Some(Point(5, 0)));
 
     let mut iter = maze.iter();
-    assert_eq!(iter.next(), Some(Cell(5, 0)));
-    assert_eq!(iter.next(), Some(Cell(5, 1)));
-    assert_eq!(iter.next(), Some(Cell(5, 2)));
-    assert_eq!(iter.next(), Some(Cell(5, 3)));
-    assert_eq!(iter.next(), Some(Cell(5, 4)));
-    assert_eq!(iter.next(), Some(Cell(5, 5)));
-    assert_eq!(iter.next(), Some(Cell(6, 5)));
-
-    assert_eq!(maze.find_treasure().iter().collect::<String>(), "ABCDEF");
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(5, 0)));
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(5, 1)));
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(5, 2)));
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(5, 3)));
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(5, 4)));
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(5, 5)));
+    assert_eq!(iter.next().map(Result::unwrap), Some(Point(6, 5)));
+
+    assert_eq!(maze.find_treasure().unwrap().iter().collect::<String>(), "ABCDEF");
+}
+
+#[test]
+fn test_maze_walk_error() {
+    let forked = Maze::from_map("  |  \n -+- ").unwrap();
+    assert_eq!(forked.find_treasure().unwrap_err().to_string(), "line 2, column 3: the path splits at a corner");
+
+    let dead_end = Maze::from_map(" | \n + ").unwrap();
+    assert_eq!(Day19.part2(&dead_end).unwrap_err().to_string(), "line 2, column 2: the path ends at a corner");
+}
+
+#[test]
+fn test_maze_parse_error() {
+    let err = Maze::from_map("  |  \n  |  \n  +-* ").err().unwrap();
+    assert_eq!(err.to_string(), "line 3, column 5: unexpected character '*' in the diagram");
 }
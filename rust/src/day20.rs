@@ -1,32 +1,48 @@
-use common;
+use error::{self, AdventError, Result};
+use solution::Solution;
 use std::ops::Add;
 use std::collections::HashMap;
 use regex::Regex;
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/20.txt").expect("expected input 20.txt");
-    {
-        let mut particles = input.trim().lines().map(|line| {
-            Particle::parse(line)
-        }).collect::<Vec<_>>();
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+
+    type Input = Vec<Particle>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<Particle>> {
+        error::parse_lines(input.trim(), Particle::parse)
+    }
+
+    fn part1(&self, particles: &Vec<Particle>) -> Result<usize> {
+        let mut particles = particles.clone();
         for _ in 1..1000 {
             for particle in particles.iter_mut() {
                 particle.update();
             }
         }
-        let closest = particles.iter().enumerate().min_by_key(|&(_i, p)| p.distance_from_center()).unwrap();
-        println!("Part 1: Particle {:?}", closest.0);
+        let closest = particles.iter().enumerate().min_by_key(|&(_i, p)| p.distance_from_center())
+            .ok_or_else(|| AdventError::solve("there are no particles"))?;
+        Ok(closest.0)
     }
 
-    {
-        let particles = input.trim().lines().map(|line| {
-            Particle::parse(line)
-        }).collect::<Vec<_>>();
-        let mut collider = Collider::new(particles);
+    fn part2(&self, particles: &Vec<Particle>) -> Result<usize> {
+        let mut collider = Collider::new(particles.clone());
         for _ in 1..1000 {
             collider.tick();
         }
-        println!("Part 2: {} particles left", collider.count());
+        Ok(collider.count())
+    }
+
+    fn describe_part1(&self, closest: &usize) -> String {
+        format!("Particle {}", closest)
+    }
+
+    fn describe_part2(&self, count: &usize) -> String {
+        format!("{} particles left", count)
     }
 }
 
@@ -49,25 +65,28 @@ fn test_vec3() {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Particle(Vec3, Vec3, Vec3);
+pub struct Particle(Vec3, Vec3, Vec3);
 
 impl Particle {
-    fn parse(s: &str) -> Self {
+    fn parse(s: &str) -> Result<Self> {
         lazy_static! {
             static ref PARTICLE_REGEX: Regex =
                 Regex::new(r"p=<(-?\d+),(-?\d+),(-?\d+)>, v=<(-?\d+),(-?\d+),(-?\d+)>, a=<(-?\d+),(-?\d+),(-?\d+)>").unwrap();
         }
 
-        let captures = PARTICLE_REGEX.captures(s).expect("Regex didn't match line");
+        let captures = PARTICLE_REGEX.captures(s)
+            .ok_or_else(|| AdventError::parse("expected a particle like p=<x,y,z>, v=<x,y,z>, a=<x,y,z>"))?;
         let mut nums = vec![];
         for i in 1..10 {
-            let num: i64 = captures[i].parse::<i64>().unwrap();
+            let num: i64 = captures[i].parse::<i64>().map_err(|_| {
+                AdventError::parse(format!("number {} is out of range", &captures[i])).at(s, &captures[i])
+            })?;
             nums.push(num);
         }
         let position = Vec3(nums[0], nums[1], nums[2]);
         let velocity = Vec3(nums[3], nums[4], nums[5]);
         let acceleration = Vec3(nums[6], nums[7], nums[8]);
-        Particle(position, velocity, acceleration)
+        Ok(Particle(position, velocity, acceleration))
     }
 
     fn update(&mut self) {
@@ -86,12 +105,13 @@ impl Particle {
 #[test]
 fn test_particle() {
     assert_eq!(
-        Particle::parse("p=<3,-2,1>, v=<2,-1,-3>, a=<-1,-2,-3>"),
+        Particle::parse("p=<3,-2,1>, v=<2,-1,-3>, a=<-1,-2,-3>").unwrap(),
         Particle(Vec3(3, -2, 1), Vec3(2, -1, -3), Vec3(-1, -2, -3))
     );
 
-    let mut p1 = Particle::parse("p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>");
-    let mut p2 = Particle::parse("p=<4,0,0>, v=<0,0,0>, a=<-2,0,0>");
+    let mut p1 = Particle::parse("p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>").unwrap();
+    let mut p2 = Particle::parse("p=<4,0,0>, v=<0,0,0>, a=<-2,0,0>").unwrap();
+    assert!(Particle::parse("p=<3,0>, v=<2,0,0>, a=<-1,0,0>").is_err());
 
     p1.update();
     p2.update();
@@ -141,10 +161,10 @@ impl Collider {
 
 #[test]
 fn test_collider() {
-    let p1 = Particle::parse("p=<-6,0,0>, v=<3,0,0>, a=<0,0,0>");
-    let p2 = Particle::parse("p=<-4,0,0>, v=<2,0,0>, a=<0,0,0>");
-    let p3 = Particle::parse("p=<-2,0,0>, v=<1,0,0>, a=<0,0,0>");
-    let p4 = Particle::parse("p=<3,0,0>, v=<-1,0,0>, a=<0,0,0>");
+    let p1 = Particle::parse("p=<-6,0,0>, v=<3,0,0>, a=<0,0,0>").unwrap();
+    let p2 = Particle::parse("p=<-4,0,0>, v=<2,0,0>, a=<0,0,0>").unwrap();
+    let p3 = Particle::parse("p=<-2,0,0>, v=<1,0,0>, a=<0,0,0>").unwrap();
+    let p4 = Particle::parse("p=<3,0,0>, v=<-1,0,0>, a=<0,0,0>").unwrap();
     let mut collider = Collider::new(vec![p1, p2, p3, p4]);
 
     assert_eq!(collider.tick(), 0);
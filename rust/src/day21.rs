@@ -1,32 +1,49 @@
-use common;
 use coords::Coord;
+use error::{self, AdventError, Result};
+use solution::Solution;
 use std::fmt;
+use std::str::FromStr;
 
-pub fn run(_args: &[String]) {
-    let input = common::get_input("./inputs/21.txt").expect("expected input 21.txt");
-    let rulebook = Rulebook::from_str(&input);
+pub struct Day21;
 
-    {
-        // Part 1
-        let mut pixbuf = PixBuf::from_str(".#./..#/###");
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+
+    type Input = Rulebook;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Rulebook> {
+        Rulebook::from_str(input)
+    }
+
+    fn part1(&self, rulebook: &Rulebook) -> Result<usize> {
+        let mut pixbuf = PixBuf::from_str(".#./..#/###")?;
         for _i in 0..5 {
-            pixbuf = iterate(&pixbuf, &rulebook);
+            pixbuf = iterate(&pixbuf, rulebook)?;
         }
-        let count = pixbuf.pixels.iter().filter(|&p| *p == true).count();
-        println!("Part 1: {} live pixels", count);
+        Ok(pixbuf.pixels.iter().filter(|&&p| p).count())
     }
-    {
-        // Part 2: run with `--release`
-        let mut pixbuf = PixBuf::from_str(".#./..#/###");
+
+    fn part2(&self, rulebook: &Rulebook) -> Result<usize> {
+        // Run with `--release`
+        let mut pixbuf = PixBuf::from_str(".#./..#/###")?;
         for _i in 0..18 {
-            pixbuf = iterate(&pixbuf, &rulebook);
+            pixbuf = iterate(&pixbuf, rulebook)?;
         }
-        let count = pixbuf.pixels.iter().filter(|&p| *p == true).count();
-        println!("Part 2: {} live pixels", count);
+        Ok(pixbuf.pixels.iter().filter(|&&p| p).count())
+    }
+
+    fn describe_part1(&self, count: &usize) -> String {
+        format!("{} live pixels", count)
+    }
+
+    fn describe_part2(&self, count: &usize) -> String {
+        format!("{} live pixels", count)
     }
 }
 
-fn iterate(before: &PixBuf, rules: &Rulebook) -> PixBuf {
+fn iterate(before: &PixBuf, rules: &Rulebook) -> Result<PixBuf> {
     let size = before.size;
     let parts;
     if size % 2 == 0 {
@@ -34,45 +51,67 @@ fn iterate(before: &PixBuf, rules: &Rulebook) -> PixBuf {
     } else if size % 3 == 0 {
         parts = before.split_into_sized(3);
     } else {
-        panic!("Size divisible by neither 2 nor 3");
+        return Err(AdventError::solve(format!("size {} is divisible by neither 2 nor 3", size)));
     }
 
-    let new_parts = parts.iter().map(|part| rules.get_replacement(part).unwrap()).collect::<Vec<_>>();
-    PixBuf::stitch(&new_parts)
+    let new_parts = parts.iter().map(|part| {
+        rules.get_replacement(part).ok_or_else(|| AdventError::solve(format!("no rule matches {:?}", part)))
+    }).collect::<Result<Vec<_>>>()?;
+    Ok(PixBuf::stitch(&new_parts))
 }
 
 
-enum Axis {
+pub enum Axis {
     VERTICAL, HORIZONTAL
 }
 
+/// A square image of on/off pixels, stored row by row.
+///
+/// This stays a flat vector rather than a `coords::Grid`: matching rules
+/// rotates, flips and compares millions of tiny images, and going through
+/// `Grid`'s bounds-checked lookups for every pixel makes day 21 take well
+/// over half as long again.
 #[derive(PartialEq, Eq, Hash, Clone)]
-struct PixBuf {
+pub struct PixBuf {
     size: usize,
     pixels: Vec<bool>,
 }
 
-impl PixBuf {
-    fn from_str(string: &str) -> Self {
-        let parts = string.trim().split("/").collect::<Vec<_>>();
+impl FromStr for PixBuf {
+    type Err = AdventError;
+
+    /// Parses a pattern like `.#./..#/###`, with rows separated by slashes.
+    fn from_str(string: &str) -> Result<Self> {
+        let string = string.trim();
+        let parts = string.split("/").collect::<Vec<_>>();
         let size = parts.len();
-        assert!(size > 0);
-        let pixels = parts.join("").chars().map(|ch| {
-            match ch {
-                '#' => true,
-                '.' => false,
-                _   => panic!("Invalid pattern character")
+        let mut pixels = Vec::with_capacity(size * size);
+        for part in parts {
+            if part.chars().count() != size {
+                return Err(AdventError::parse(format!("expected a row of {} pixels", size)).at(string, part));
             }
-        }).collect::<Vec<_>>();
-        PixBuf { size, pixels }
+            for (idx, ch) in part.char_indices() {
+                match ch {
+                    '#' => pixels.push(true),
+                    '.' => pixels.push(false),
+                    _   => {
+                        let message = format!("invalid pattern character '{}'", ch);
+                        return Err(AdventError::parse(message).at(string, &part[idx..]));
+                    }
+                }
+            }
+        }
+        Ok(PixBuf { size, pixels })
     }
+}
 
-    fn with_size(size: usize) -> Self {
+impl PixBuf {
+    pub fn with_size(size: usize) -> Self {
         let pixels = vec![false; size.pow(2)];
         PixBuf { size, pixels }
     }
 
-    fn stitch(pixbufs: &Vec<&PixBuf>) -> Self {
+    pub fn stitch(pixbufs: &Vec<&PixBuf>) -> Self {
         let len = pixbufs.len();
         assert!(len > 0);
         let size = pixbufs[0].size;
@@ -98,11 +137,11 @@ impl PixBuf {
         y * self.size + x
     }
 
-    fn get_pixel(&self, coord: Coord) -> bool {
+    pub fn get_pixel(&self, coord: Coord) -> bool {
         self.pixels[self.index_for_coord(coord)]
     }
 
-    fn set_pixel(&mut self, coord: Coord, value: bool) -> &mut Self {
+    pub fn set_pixel(&mut self, coord: Coord, value: bool) -> &mut Self {
         let index = self.index_for_coord(coord);
         self.pixels[index] = value;
         self
@@ -129,8 +168,8 @@ impl PixBuf {
         self
     }
 
-    fn split_into_sized(&self, size: usize) -> Vec<PixBuf> {
-        assert!(self.size % size == 0);
+    pub fn split_into_sized(&self, size: usize) -> Vec<PixBuf> {
+        assert!(self.size.is_multiple_of(size));
         let half = self.size / size;
         let mut result = Vec::with_capacity(half);
         // Iterate columns-per-row first
@@ -156,7 +195,7 @@ impl PixBuf {
         buf
     }
 
-    fn flip(&mut self, across_axis: Axis) -> &mut Self {
+    pub fn flip(&mut self, across_axis: Axis) -> &mut Self {
         let mid = self.size / 2;
 
         for y in 0..self.size {
@@ -182,7 +221,7 @@ impl PixBuf {
         self
     }
 
-    fn rotate(&mut self) -> &mut Self {
+    pub fn rotate(&mut self) -> &mut Self {
         // Transpose...
         for i in 0..(self.size - 1) {
             for j in (i+1)..(self.size) {
@@ -196,7 +235,7 @@ impl PixBuf {
         self.flip(Axis::HORIZONTAL)
     }
 
-    fn to_string(&self) -> String {
+    pub fn to_string(&self) -> String {
         self.pixels.chunks(self.size).map(|chunk| {
             chunk.iter().map(|b| if *b { "#" } else { "." }).collect::<String>()
         }).collect::<Vec<_>>().join("/")
@@ -211,19 +250,19 @@ impl fmt::Debug for PixBuf {
 
 #[test]
 fn test_pixbuf_flipping() {
-    let pix1 = PixBuf::from_str("#.#/.#./##.");
-    let flipped_vert = PixBuf::from_str("##./.#./#.#");
-    let flipped_horiz = PixBuf::from_str("#.#/.#./.##");
+    let pix1 = PixBuf::from_str("#.#/.#./##.").unwrap();
+    let flipped_vert = PixBuf::from_str("##./.#./#.#").unwrap();
+    let flipped_horiz = PixBuf::from_str("#.#/.#./.##").unwrap();
     assert_eq!(pix1.clone().flip(Axis::VERTICAL), &flipped_vert);
     assert_eq!(pix1.clone().flip(Axis::HORIZONTAL), &flipped_horiz);
 }
 
 #[test]
 fn test_pixbuf_rotation() {
-    let mut pix1 = PixBuf::from_str("#.#/..#/.#.");
-    let rotate1 = PixBuf::from_str("..#/#../.##");
-    let rotate2 = PixBuf::from_str(".#./#../#.#");
-    let rotate3 = PixBuf::from_str("##./..#/#..");
+    let mut pix1 = PixBuf::from_str("#.#/..#/.#.").unwrap();
+    let rotate1 = PixBuf::from_str("..#/#../.##").unwrap();
+    let rotate2 = PixBuf::from_str(".#./#../#.#").unwrap();
+    let rotate3 = PixBuf::from_str("##./..#/#..").unwrap();
     let rotate4 = pix1.clone();
     assert_eq!(pix1.rotate(), &rotate1);
     assert_eq!(pix1.rotate(), &rotate2);
@@ -233,15 +272,15 @@ fn test_pixbuf_rotation() {
 
 #[test]
 fn test_pixbuf_apply() {
-    let mut pix1 = PixBuf::from_str("..../..../..../....");
-    let pix2 = PixBuf::from_str("##/##");
+    let mut pix1 = PixBuf::from_str("..../..../..../....").unwrap();
+    let pix2 = PixBuf::from_str("##/##").unwrap();
     pix1.copy_from(&pix2, Coord(1, 1));
     assert_eq!(pix1.to_string(), "..../.##./.##./....");
 }
 
 #[test]
 fn test_pixbuf_split() {
-    let pix1 = PixBuf::from_str(".##./#..#/..##/##..");
+    let pix1 = PixBuf::from_str(".##./#..#/..##/##..").unwrap();
     let split = pix1.split_into_sized(2);
     let strings = split.iter().map(|pb| pb.to_string()).collect::<Vec<_>>();
     assert_eq!(strings, vec![
@@ -251,27 +290,30 @@ fn test_pixbuf_split() {
 
 #[test]
 fn test_pixbuf_stitch() {
-    let pb1 = PixBuf::from_str(".#/#.");
-    let pb2 = PixBuf::from_str("#./.#");
-    let pb3 = PixBuf::from_str("../##");
-    let pb4 = PixBuf::from_str("##/..");
+    let pb1 = PixBuf::from_str(".#/#.").unwrap();
+    let pb2 = PixBuf::from_str("#./.#").unwrap();
+    let pb3 = PixBuf::from_str("../##").unwrap();
+    let pb4 = PixBuf::from_str("##/..").unwrap();
     let pixbufs = vec![&pb1, &pb2, &pb3, &pb4];
-    assert_eq!(PixBuf::from_str(".##./#..#/..##/##.."), PixBuf::stitch(&pixbufs));
+    assert_eq!(PixBuf::from_str(".##./#..#/..##/##..").unwrap(), PixBuf::stitch(&pixbufs));
 }
 
 
-#[derive(Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
 struct Rule {
     pattern: PixBuf,
     replacement: PixBuf,
 }
 
 impl Rule {
-    fn from_str(string: &str) -> Self {
+    fn from_str(string: &str) -> Result<Self> {
         let parts = string.split(" => ").collect::<Vec<_>>();
-        let pattern = PixBuf::from_str(parts[0]);
-        let replacement = PixBuf::from_str(parts[1]);
-        Rule::new(pattern, replacement)
+        if parts.len() != 2 {
+            return Err(AdventError::parse("expected 'pattern => replacement'"));
+        }
+        let pattern = PixBuf::from_str(parts[0]).map_err(|err| err.at(string, parts[0]))?;
+        let replacement = PixBuf::from_str(parts[1]).map_err(|err| err.at(string, parts[1]))?;
+        Ok(Rule::new(pattern, replacement))
     }
 
     fn new(pattern: PixBuf, replacement: PixBuf) -> Self {
@@ -316,8 +358,8 @@ impl Rule {
 
 #[test]
 fn test_rule_matching() {
-    let pattern = PixBuf::from_str(".#./..#/###");
-    let replacement = PixBuf::from_str("#..#/..../..../#..#");
+    let pattern = PixBuf::from_str(".#./..#/###").unwrap();
+    let replacement = PixBuf::from_str("#..#/..../..../#..#").unwrap();
     let rule = Rule::new(pattern.clone(), replacement);
 
     for i in 0..5 {
@@ -335,14 +377,15 @@ fn test_rule_matching() {
 }
 
 
-struct Rulebook {
+#[derive(Debug)]
+pub struct Rulebook {
     rules: Vec<Rule>
 }
 
 impl Rulebook {
-    fn from_str(string: &str) -> Self {
-        let rules = string.trim().lines().map(Rule::from_str).collect::<Vec<_>>();
-        Rulebook { rules }
+    fn from_str(string: &str) -> Result<Self> {
+        let rules = error::parse_lines(string.trim(), Rule::from_str)?;
+        Ok(Rulebook { rules })
     }
 
     fn get_replacement(&self, search: &PixBuf) -> Option<&PixBuf> {
@@ -357,8 +400,12 @@ impl Rulebook {
 #[test]
 fn test_parse_rulebook() {
     let rulebook_text = "../.# => ##./#../...\n.#./..#/### => #..#/..../..../#..#";
-    let rulebook = Rulebook::from_str(rulebook_text);
-    let search = PixBuf::from_str(".#./..#/###");
-    let replacement = PixBuf::from_str("#..#/..../..../#..#");
+    let rulebook = Rulebook::from_str(rulebook_text).unwrap();
+    let search = PixBuf::from_str(".#./..#/###").unwrap();
+    let replacement = PixBuf::from_str("#..#/..../..../#..#").unwrap();
     assert_eq!(rulebook.get_replacement(&search), Some(&replacement));
+
+    let err = Rulebook::from_str("../.# => ##./#../...\n.#./..#/### => #..#/..x./..../#..#").unwrap_err();
+    assert_eq!(err.to_string(), "line 2, column 23: invalid pattern character 'x'");
+    assert!(Rulebook::from_str("../.# => ##./#../..").is_err());
 }
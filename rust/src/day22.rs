@@ -0,0 +1,139 @@
+use coords::{Direction4, Point};
+use error::{self, AdventError, Result};
+use solution::Solution;
+use std::collections::HashMap;
+
+pub struct Day22;
+
+/// The starting grid: the positions of the infected nodes, and the
+/// position of the middle node, where the virus carrier starts.
+pub struct Cluster {
+    infected: Vec<Point>,
+    center: Point,
+}
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+
+    type Input = Cluster;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Cluster> {
+        let rows = error::parse_lines(input.trim(), |line| {
+            line.char_indices().map(|(idx, ch)| match ch {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _   => Err(AdventError::parse(format!("expected # or ., found '{}'", ch)).at(line, &line[idx..])),
+            }).collect::<Result<Vec<bool>>>()
+        })?;
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(AdventError::parse("every row of the grid must be the same width"));
+        }
+        if width % 2 == 0 || rows.len() % 2 == 0 {
+            return Err(AdventError::parse("the grid must have a middle node"));
+        }
+
+        let mut infected = vec![];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &node) in row.iter().enumerate() {
+                if node {
+                    infected.push(Point(x as i64, y as i64));
+                }
+            }
+        }
+
+        Ok(Cluster { infected, center: Point(width as i64 / 2, rows.len() as i64 / 2) })
+    }
+
+    fn part1(&self, cluster: &Cluster) -> Result<usize> {
+        Ok(Carrier::new(cluster, Mode::Simple).burst(10_000))
+    }
+
+    fn part2(&self, cluster: &Cluster) -> Result<usize> {
+        Ok(Carrier::new(cluster, Mode::Evolved).burst(10_000_000))
+    }
+
+    fn describe_part1(&self, count: &usize) -> String {
+        format!("{} bursts caused an infection", count)
+    }
+
+    fn describe_part2(&self, count: &usize) -> String {
+        format!("{} bursts caused an infection with the evolved virus", count)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum NodeState {
+    Clean, Weakened, Infected, Flagged
+}
+
+/// The original virus only toggles nodes between clean and infected;
+/// the evolved one passes through weakened and flagged along the way.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mode {
+    Simple, Evolved
+}
+
+impl Mode {
+    fn next_state(&self, state: NodeState) -> NodeState {
+        use self::NodeState::*;
+        match (*self, state) {
+            (Mode::Simple, Clean)     => Infected,
+            (Mode::Simple, _)         => Clean,
+            (Mode::Evolved, Clean)    => Weakened,
+            (Mode::Evolved, Weakened) => Infected,
+            (Mode::Evolved, Infected) => Flagged,
+            (Mode::Evolved, Flagged)  => Clean,
+        }
+    }
+}
+
+/// The virus carrier, wandering the grid.
+pub struct Carrier {
+    nodes: HashMap<Point, NodeState>,
+    position: Point,
+    facing: Direction4,
+    mode: Mode,
+}
+
+impl Carrier {
+    pub fn new(cluster: &Cluster, mode: Mode) -> Self {
+        let nodes = cluster.infected.iter().map(|&pos| (pos, NodeState::Infected)).collect();
+        Carrier { nodes, position: cluster.center, facing: Direction4::North, mode }
+    }
+
+    /// Runs the given number of bursts, returning how many of them infected a node.
+    pub fn burst(&mut self, bursts: usize) -> usize {
+        (0..bursts).filter(|_| self.step()).count()
+    }
+
+    fn step(&mut self) -> bool {
+        let state = *self.nodes.get(&self.position).unwrap_or(&NodeState::Clean);
+        self.facing = match state {
+            NodeState::Clean    => self.facing.turn_left(),
+            NodeState::Weakened => self.facing,
+            NodeState::Infected => self.facing.turn_right(),
+            NodeState::Flagged  => self.facing.opposite(),
+        };
+
+        let next = self.mode.next_state(state);
+        self.nodes.insert(self.position, next);
+        self.position = self.position + self.facing;
+        next == NodeState::Infected
+    }
+}
+
+#[test]
+fn test_carrier() {
+    let cluster = Day22.parse("..#\n#..\n...").unwrap();
+    assert_eq!(cluster.center, Point(1, 1));
+    assert_eq!(Carrier::new(&cluster, Mode::Simple).burst(7), 5);
+    assert_eq!(Carrier::new(&cluster, Mode::Simple).burst(70), 41);
+    assert_eq!(Carrier::new(&cluster, Mode::Simple).burst(10_000), 5587);
+    assert_eq!(Carrier::new(&cluster, Mode::Evolved).burst(100), 26);
+
+    assert!(Day22.parse("..#\n#..").is_err());
+    assert!(Day22.parse("..#\n#x.\n...").is_err());
+}
@@ -0,0 +1,185 @@
+use day18::{Machine, RegisterValue};
+use error::{self, AdventError, Result};
+use solution::Solution;
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+
+    type Input = Vec<Instruction>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>> {
+        error::parse_lines(input.trim(), Instruction::parse)
+    }
+
+    fn part1(&self, instructions: &Vec<Instruction>) -> Result<usize> {
+        let mut coprocessor = Coprocessor::new(instructions);
+        while coprocessor.step()? {}
+        Ok(coprocessor.mul_count())
+    }
+
+    /// With `a` set to 1, the program counts the composite numbers from `b`
+    /// to `c` in steps of 17, but far too slowly to run directly. So we run
+    /// just enough of it to find `b` and `c`, and do the counting ourselves.
+    fn part2(&self, instructions: &Vec<Instruction>) -> Result<usize> {
+        let loop_start = instructions.iter().position(|instr| *instr == Instruction::Set("f".to_string(), RegisterValue::Value(1)))
+            .ok_or_else(|| AdventError::solve("couldn't find the start of the main loop (set f 1)"))?;
+        let step = instructions[loop_start..].iter().filter_map(|instr| match *instr {
+            Instruction::Sub(ref reg, RegisterValue::Value(n)) if reg == "b" && n < 0 => Some(-n),
+            _ => None,
+        }).next().ok_or_else(|| AdventError::solve("couldn't find the step for b (sub b -N)"))?;
+
+        let mut coprocessor = Coprocessor::new(instructions);
+        coprocessor.machine.set_register(&"a".to_string(), 1);
+        while coprocessor.pointer != loop_start as i64 && coprocessor.step()? {}
+        let (b, c) = (coprocessor.machine.get_register(&"b".to_string()), coprocessor.machine.get_register(&"c".to_string()));
+        if b > c {
+            return Err(AdventError::solve(format!("b ({}) is already past c ({})", b, c)));
+        }
+
+        Ok((b..c + 1).step_by(step as usize).filter(|&n| is_composite(n)).count())
+    }
+
+    fn describe_part1(&self, count: &usize) -> String {
+        format!("mul was invoked {} times", count)
+    }
+
+    fn describe_part2(&self, h: &usize) -> String {
+        format!("Register h is left at {}", h)
+    }
+}
+
+/// The coprocessor's dialect of the day 18 instructions: `set` and `mul`
+/// work as in Duet, `sub` subtracts, and `jnz` jumps if its first value
+/// isn't zero. Nothing else is understood.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Instruction {
+    Set(String, RegisterValue),
+    Sub(String, RegisterValue),
+    Mul(String, RegisterValue),
+    Jnz(RegisterValue, RegisterValue),
+}
+
+impl Instruction {
+    pub fn parse(instr: &str) -> Result<Self> {
+        let mut parts = instr.split(' ');
+        let mut next = |what| error::next_token(&mut parts, instr, what);
+        let name = next("an instruction")?;
+        match name {
+            "set" | "sub" | "mul" => {
+                let reg = next("a register")?.to_string();
+                let val = RegisterValue::parse(next("a value")?);
+                Ok(match name {
+                    "set" => Instruction::Set(reg, val),
+                    "sub" => Instruction::Sub(reg, val),
+                    _     => Instruction::Mul(reg, val),
+                })
+            },
+            "jnz" => {
+                let cond = next("a value")?;
+                let offset = next("an offset")?;
+                Ok(Instruction::Jnz(RegisterValue::parse(cond), RegisterValue::parse(offset)))
+            },
+            _ => Err(AdventError::parse(format!("unknown instruction '{}'", name)).at(instr, name))
+        }
+    }
+}
+
+/// Runs the coprocessor's instructions one step at a time.
+pub struct Coprocessor<'a> {
+    machine: Machine,
+    instructions: &'a [Instruction],
+    pointer: i64,
+    mul_count: usize,
+}
+
+impl<'a> Coprocessor<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        Coprocessor { machine: Machine::new(), instructions, pointer: 0, mul_count: 0 }
+    }
+
+    /// Runs the next instruction, returning `false` once the program has
+    /// finished by moving just past its last instruction. A jump to anywhere
+    /// else outside the program is an error.
+    pub fn step(&mut self) -> Result<bool> {
+        use self::Instruction::*;
+
+        if self.pointer as usize == self.instructions.len() {
+            return Ok(false);
+        }
+
+        let machine = &mut self.machine;
+        match self.instructions[self.pointer as usize] {
+            Set(ref reg, ref val) => {
+                let value = machine.resolve_value(val);
+                machine.set_register(reg, value);
+            },
+            Sub(ref reg, ref val) => {
+                let value = machine.get_register(reg) - machine.resolve_value(val);
+                machine.set_register(reg, value);
+            },
+            Mul(ref reg, ref val) => {
+                let value = machine.get_register(reg) * machine.resolve_value(val);
+                machine.set_register(reg, value);
+                self.mul_count += 1;
+            },
+            Jnz(ref cond, ref offset) => {
+                if machine.resolve_value(cond) != 0 {
+                    let target = self.pointer + machine.resolve_value(offset);
+                    if target < 0 || target as usize > self.instructions.len() {
+                        return Err(AdventError::solve(format!("jnz jumps to {}, outside the program", target))
+                            .on_line(self.pointer as usize + 1));
+                    }
+                    self.pointer = target;
+                    return Ok(true);
+                }
+            },
+        }
+
+        self.pointer += 1;
+        Ok(true)
+    }
+
+    pub fn mul_count(&self) -> usize {
+        self.mul_count
+    }
+
+    pub fn register(&self, register: &str) -> i64 {
+        self.machine.get_register(&register.to_string())
+    }
+}
+
+fn is_composite(n: i64) -> bool {
+    n > 3 && (2..).take_while(|d| d * d <= n).any(|d| n % d == 0)
+}
+
+#[test]
+fn test_coprocessor() {
+    let instructions = Day23.parse("set a 3\nmul b a\nsub a 1\njnz a -2\nmul b b").unwrap();
+    let mut coprocessor = Coprocessor::new(&instructions);
+    while coprocessor.step().unwrap() {}
+    assert_eq!(coprocessor.register("a"), 0);
+    assert_eq!(coprocessor.register("b"), 0);
+    assert_eq!(coprocessor.mul_count(), 4);
+    assert_eq!(Day23.part1(&instructions).unwrap(), 4);
+
+    let runaway = Day23.parse("set a 1\njnz a -2").unwrap();
+    assert_eq!(Day23.part1(&runaway).unwrap_err().to_string(), "line 2: jnz jumps to -1, outside the program");
+    let runaway = Day23.parse("jnz 1 3\nset a 1").unwrap();
+    assert_eq!(Day23.part1(&runaway).unwrap_err().to_string(), "line 1: jnz jumps to 3, outside the program");
+
+    let counter = Day23.parse("set b 9\nset c 31\nset f 1\nsub b -5").unwrap();
+    assert_eq!(Day23.part2(&counter).unwrap(), 3);
+
+    assert_eq!(Day23.parse("set a 1\nsnd a").unwrap_err().to_string(), "line 2, column 1: unknown instruction 'snd'");
+    assert_eq!(Day23.parse("jnz a").unwrap_err().to_string(), "line 1, column 6: expected an offset");
+}
+
+#[test]
+fn test_is_composite() {
+    let composites: Vec<i64> = (1..16).filter(|&n| is_composite(n)).collect();
+    assert_eq!(composites, vec![4, 6, 8, 9, 10, 12, 14, 15]);
+}
@@ -0,0 +1,110 @@
+use error::{self, AdventError, Result};
+use solution::Solution;
+
+pub struct Day24;
+
+/// A magnetic component with a port on each end.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Component(u32, u32);
+
+impl Component {
+    /// Parses a component like `0/2`.
+    fn parse(line: &str) -> Result<Self> {
+        let idx = line.find('/').ok_or_else(|| AdventError::parse("expected a component like 0/2"))?;
+        let (a, b) = (&line[..idx], &line[idx+1..]);
+        let port = |s: &str| {
+            s.trim().parse().map_err(|_| AdventError::parse(format!("couldn't parse port '{}'", s)).at(line, s))
+        };
+        Ok(Component(port(a)?, port(b)?))
+    }
+
+    fn strength(&self) -> u32 {
+        self.0 + self.1
+    }
+
+    /// The port left free when connecting this component by a port with `pins` pins.
+    fn other_end(&self, pins: u32) -> Option<u32> {
+        if self.0 == pins {
+            Some(self.1)
+        } else if self.1 == pins {
+            Some(self.0)
+        } else {
+            None
+        }
+    }
+}
+
+/// The length and strength of a bridge.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+struct Bridge {
+    length: usize,
+    strength: u32,
+}
+
+impl Solution for Day24 {
+    const DAY: u32 = 24;
+
+    type Input = Vec<Component>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Component>> {
+        error::parse_lines(input.trim(), Component::parse)
+    }
+
+    fn part1(&self, components: &Vec<Component>) -> Result<u32> {
+        Ok(best_bridge(components, |bridge| (bridge.strength, 0)).strength)
+    }
+
+    fn part2(&self, components: &Vec<Component>) -> Result<u32> {
+        Ok(best_bridge(components, |bridge| (bridge.length as u32, bridge.strength)).strength)
+    }
+
+    fn describe_part1(&self, strength: &u32) -> String {
+        format!("The strongest bridge has a strength of {}", strength)
+    }
+
+    fn describe_part2(&self, strength: &u32) -> String {
+        format!("The longest bridge has a strength of {}", strength)
+    }
+}
+
+/// Searches every bridge that can be built starting from a zero-pin port,
+/// keeping whichever one `rank` likes best.
+fn best_bridge<F>(components: &[Component], rank: F) -> Bridge
+    where F: Fn(&Bridge) -> (u32, u32)
+{
+    let mut used = vec![false; components.len()];
+    let mut best = Bridge::default();
+    extend(components, &mut used, 0, Bridge::default(), &rank, &mut best);
+    best
+}
+
+fn extend<F>(components: &[Component], used: &mut [bool], pins: u32, bridge: Bridge, rank: &F, best: &mut Bridge)
+    where F: Fn(&Bridge) -> (u32, u32)
+{
+    if rank(&bridge) > rank(best) {
+        *best = bridge;
+    }
+
+    for (idx, component) in components.iter().enumerate() {
+        if used[idx] {
+            continue;
+        }
+        if let Some(next_pins) = component.other_end(pins) {
+            used[idx] = true;
+            let longer = Bridge { length: bridge.length + 1, strength: bridge.strength + component.strength() };
+            extend(components, used, next_pins, longer, rank, best);
+            used[idx] = false;
+        }
+    }
+}
+
+#[test]
+fn test_bridges() {
+    let components = Day24.parse("0/2\n2/2\n2/3\n3/4\n3/5\n0/1\n10/1\n9/10").unwrap();
+    assert_eq!(components[0], Component(0, 2));
+    assert_eq!(Day24.part1(&components).unwrap(), 31);
+    assert_eq!(Day24.part2(&components).unwrap(), 19);
+    assert_eq!(Day24.parse("0/2\n2-2").unwrap_err().to_string(), "line 2: expected a component like 0/2");
+}
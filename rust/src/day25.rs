@@ -0,0 +1,215 @@
+use error::{AdventError, Result};
+use solution::Solution;
+use std::collections::{HashMap, VecDeque};
+
+pub struct Day25;
+
+/// What to do when reading one value in one state.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Action {
+    write: bool,
+    step: i64,
+    next: char,
+}
+
+/// The Turing machine's blueprint: the state it starts in, when to take
+/// the checksum, and for each state, what to do on reading a 0 or a 1.
+#[derive(Debug, PartialEq)]
+pub struct Blueprint {
+    start: char,
+    steps: usize,
+    states: HashMap<char, [Action; 2]>,
+}
+
+/// The lines of a blueprint, skipping blank ones, along with their line numbers.
+struct Lines<'a> {
+    lines: Box<dyn Iterator<Item = (usize, &'a str)> + 'a>,
+    last: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        let lines = text.lines().enumerate().map(|(idx, line)| (idx + 1, line.trim())).filter(|&(_, line)| !line.is_empty());
+        Lines { lines: Box::new(lines), last: 0 }
+    }
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        let next = self.lines.next();
+        if let Some((number, _)) = next {
+            self.last = number;
+        }
+        next
+    }
+
+    /// Reads the next line, which must look like `prefix VALUE suffix`, returning VALUE.
+    fn field(&mut self, prefix: &str, suffix: &str) -> Result<&'a str> {
+        let (number, line) = self.next()
+            .ok_or_else(|| AdventError::parse(format!("expected '{}...', but the blueprint ended", prefix)).on_line(self.last + 1))?;
+        if !line.starts_with(prefix) || !line.ends_with(suffix) || line.len() < prefix.len() + suffix.len() {
+            return Err(AdventError::parse(format!("expected '{}...{}'", prefix, suffix)).on_line(number));
+        }
+        Ok(&line[prefix.len()..line.len() - suffix.len()])
+    }
+
+    fn state(&mut self, prefix: &str) -> Result<char> {
+        let name = self.field(prefix, ".")?;
+        single_char(name).ok_or_else(|| AdventError::parse(format!("'{}' isn't a state name", name)).on_line(self.last))
+    }
+
+    fn action(&mut self, value: u8) -> Result<Action> {
+        self.field(&format!("If the current value is {}", value), ":")?;
+        let write = match self.field("- Write the value ", ".")? {
+            "0" => false,
+            "1" => true,
+            other => return Err(AdventError::parse(format!("can't write '{}'", other)).on_line(self.last)),
+        };
+        let step = match self.field("- Move one slot to the ", ".")? {
+            "left"  => -1,
+            "right" => 1,
+            other   => return Err(AdventError::parse(format!("can't move '{}'", other)).on_line(self.last)),
+        };
+        let next = self.state("- Continue with state ")?;
+        Ok(Action { write, step, next })
+    }
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _               => None,
+    }
+}
+
+impl Blueprint {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = Lines::new(text);
+        let start = lines.state("Begin in state ")?;
+        let steps_str = lines.field("Perform a diagnostic checksum after ", " steps.")?;
+        let steps = steps_str.parse().map_err(|_| {
+            AdventError::parse(format!("couldn't parse step count '{}'", steps_str)).on_line(lines.last)
+        })?;
+
+        let mut states = HashMap::new();
+        while let Some((number, line)) = lines.next() {
+            if !line.starts_with("In state ") || !line.ends_with(':') {
+                return Err(AdventError::parse("expected 'In state X:'").on_line(number));
+            }
+            let name = single_char(&line["In state ".len()..line.len() - 1])
+                .ok_or_else(|| AdventError::parse("expected a single-letter state name").on_line(number))?;
+            let actions = [lines.action(0)?, lines.action(1)?];
+            if states.insert(name, actions).is_some() {
+                return Err(AdventError::parse(format!("state {} is described twice", name)).on_line(number));
+            }
+        }
+
+        let blueprint = Blueprint { start, steps, states };
+        blueprint.check()?;
+        Ok(blueprint)
+    }
+
+    /// Makes sure every state the blueprint refers to is described.
+    fn check(&self) -> Result<()> {
+        let mentioned = Some(self.start).into_iter()
+            .chain(self.states.values().flat_map(|actions| actions.iter().map(|action| action.next)));
+        for state in mentioned {
+            if !self.states.contains_key(&state) {
+                return Err(AdventError::parse(format!("state {} is never described", state)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the machine for the number of steps in the blueprint, returning
+    /// the diagnostic checksum: the number of ones left on the tape.
+    pub fn checksum(&self) -> usize {
+        let mut tape: VecDeque<bool> = VecDeque::new();
+        tape.push_back(false);
+        let mut cursor = 0usize;
+        let mut state = self.start;
+
+        for _ in 0..self.steps {
+            let action = self.states[&state][tape[cursor] as usize];
+            tape[cursor] = action.write;
+            if action.step < 0 {
+                if cursor == 0 {
+                    tape.push_front(false);
+                } else {
+                    cursor -= 1;
+                }
+            } else {
+                cursor += 1;
+                if cursor == tape.len() {
+                    tape.push_back(false);
+                }
+            }
+            state = action.next;
+        }
+
+        tape.iter().filter(|&&value| value).count()
+    }
+}
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+
+    type Input = Blueprint;
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn parse(&self, input: &str) -> Result<Blueprint> {
+        Blueprint::parse(input)
+    }
+
+    fn part1(&self, blueprint: &Blueprint) -> Result<usize> {
+        Ok(blueprint.checksum())
+    }
+
+    /// There's no puzzle for the second part on the last day.
+    fn part2(&self, _blueprint: &Blueprint) -> Result<String> {
+        Ok("Merry Christmas!".to_string())
+    }
+
+    fn describe_part1(&self, checksum: &usize) -> String {
+        format!("The diagnostic checksum is {}", checksum)
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "\
+Begin in state A.
+Perform a diagnostic checksum after 6 steps.
+
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state B.
+
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+";
+
+#[test]
+fn test_blueprint() {
+    let blueprint = Blueprint::parse(EXAMPLE).unwrap();
+    assert_eq!(blueprint.start, 'A');
+    assert_eq!(blueprint.steps, 6);
+    assert_eq!(blueprint.states[&'B'][0], Action { write: true, step: -1, next: 'A' });
+    assert_eq!(blueprint.checksum(), 3);
+
+    let err = Blueprint::parse(&EXAMPLE.replace("slot to the left.\n    - Continue with state B", "slot up.\n    - Continue with state B")).unwrap_err();
+    assert_eq!(err.to_string(), "line 11: expected '- Move one slot to the ....'");
+    assert!(Blueprint::parse(&EXAMPLE.replace("Continue with state A.\n  If", "Continue with state C.\n  If")).is_err());
+}
@@ -0,0 +1,186 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::result;
+
+pub type Result<T> = result::Result<T, AdventError>;
+
+/// What went wrong.
+#[derive(Debug)]
+pub enum Cause {
+    /// The input couldn't be read at all.
+    Io(io::Error),
+    /// The input was read, but was malformed.
+    Parse(String),
+    /// The input was well-formed, but no answer could be found for it.
+    Solve(String),
+}
+
+/// An error from reading, parsing or solving a day's puzzle, along with
+/// the day and the position in the input where it happened, when known.
+/// Lines and columns are numbered from 1.
+#[derive(Debug)]
+pub struct AdventError {
+    pub day: Option<u32>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub cause: Cause,
+}
+
+impl AdventError {
+    fn new(cause: Cause) -> Self {
+        AdventError { day: None, line: None, column: None, cause }
+    }
+
+    pub fn parse<S: Into<String>>(message: S) -> Self {
+        Self::new(Cause::Parse(message.into()))
+    }
+
+    pub fn solve<S: Into<String>>(message: S) -> Self {
+        Self::new(Cause::Solve(message.into()))
+    }
+
+    /// Sets the day the error happened on, unless it's already known.
+    pub fn on_day(mut self, day: u32) -> Self {
+        self.day = self.day.or(Some(day));
+        self
+    }
+
+    /// Sets the line the error happened on, unless it's already known.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = self.line.or(Some(line));
+        self
+    }
+
+    /// Sets the column the error happened at. Since columns are usually
+    /// found relative to a smaller piece of the input, an existing column
+    /// is shifted over by the new one rather than being replaced.
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(match self.column {
+            Some(inner) => column + inner - 1,
+            None        => column,
+        });
+        self
+    }
+
+    /// Sets the column to where `part` starts within `line`.
+    pub fn at(self, line: &str, part: &str) -> Self {
+        self.at_column(column_of(line, part))
+    }
+
+    /// Puts some context, like which item of a list was at fault,
+    /// in front of the message.
+    pub fn context<S: fmt::Display>(mut self, context: S) -> Self {
+        self.cause = match self.cause {
+            Cause::Parse(message) => Cause::Parse(format!("{}: {}", context, message)),
+            Cause::Solve(message) => Cause::Solve(format!("{}: {}", context, message)),
+            io                    => io,
+        };
+        self
+    }
+}
+
+/// The column (counting from 1) at which `part`, which must be a
+/// slice of `line`, starts.
+pub fn column_of(line: &str, part: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = part.as_ptr() as usize;
+    debug_assert!(start <= offset && offset + part.len() <= start + line.len(), "part must be a slice of line");
+    line[..offset - start].chars().count() + 1
+}
+
+/// Takes the next token from `parts`, which are slices of `line`,
+/// complaining that we expected `what` if there are none left.
+pub fn next_token<'a, I>(parts: &mut I, line: &str, what: &str) -> Result<&'a str>
+    where I: Iterator<Item = &'a str>
+{
+    parts.next().ok_or_else(|| {
+        AdventError::parse(format!("expected {}", what)).at_column(line.chars().count() + 1)
+    })
+}
+
+/// Attaches line numbers to errors from parsing each line of some input.
+pub fn parse_lines<T, F>(input: &str, mut parse: F) -> Result<Vec<T>>
+    where F: FnMut(&str) -> Result<T>
+{
+    input.lines().enumerate().map(|(idx, line)| {
+        parse(line).map_err(|err| err.on_line(idx + 1))
+    }).collect()
+}
+
+impl fmt::Display for AdventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut location = vec![];
+        if let Some(day) = self.day {
+            location.push(format!("day {}", day));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {}", line));
+        }
+        if let Some(column) = self.column {
+            location.push(format!("column {}", column));
+        }
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(", "))?;
+        }
+
+        match self.cause {
+            Cause::Io(ref err)          => write!(f, "couldn't read input: {}", err),
+            Cause::Parse(ref message)   => write!(f, "{}", message),
+            Cause::Solve(ref message)   => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for AdventError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.cause {
+            Cause::Io(ref err) => Some(err),
+            _                  => None,
+        }
+    }
+}
+
+impl From<io::Error> for AdventError {
+    fn from(err: io::Error) -> Self {
+        Self::new(Cause::Io(err))
+    }
+}
+
+#[test]
+fn test_error_display() {
+    let err = AdventError::parse("invalid operation 'foo'").at_column(3).at_column(5).on_line(2).on_line(9).on_day(8);
+    assert_eq!(err.to_string(), "day 8, line 2, column 7: invalid operation 'foo'");
+
+    let err: AdventError = io::Error::new(io::ErrorKind::NotFound, "no such file").into();
+    assert_eq!(err.on_day(3).to_string(), "day 3: couldn't read input: no such file");
+
+    assert_eq!(AdventError::solve("no safe delay").to_string(), "no safe delay");
+    assert_eq!(AdventError::parse("bad move").context("move 3").on_line(1).to_string(), "line 1: move 3: bad move");
+}
+
+#[test]
+fn test_column_of() {
+    let line = "b inc 5 if a > 1";
+    assert_eq!(column_of(line, &line[0..1]), 1);
+    assert_eq!(column_of(line, &line[6..7]), 7);
+    assert_eq!(column_of(line, line.split_whitespace().last().unwrap()), 16);
+}
+
+#[test]
+fn test_next_token() {
+    let line = "set a";
+    let mut parts = line.split(' ');
+    assert_eq!(next_token(&mut parts, line, "an instruction").unwrap(), "set");
+    assert_eq!(next_token(&mut parts, line, "a register").unwrap(), "a");
+    let err = next_token(&mut parts, line, "a value").unwrap_err();
+    assert_eq!(err.to_string(), "column 6: expected a value");
+}
+
+#[test]
+fn test_parse_lines() {
+    let parsed = parse_lines("1\n2\nx", |line| line.parse::<u32>().map_err(|_| AdventError::parse("not a number")));
+    let err = parsed.unwrap_err();
+    assert_eq!(err.line, Some(3));
+    assert_eq!(parse_lines("1\n2", |line| Ok(line.len())).unwrap(), vec![1, 1]);
+}
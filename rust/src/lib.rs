@@ -0,0 +1,92 @@
+extern crate regex;
+#[macro_use]
+extern crate lazy_static;
+
+pub mod answers;
+pub mod common;
+pub mod coords;
+pub mod cycle;
+pub mod error;
+pub mod runner;
+pub mod solution;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+#[cfg(test)]
+use answers::Answers;
+use solution::Registry;
+use std::path::PathBuf;
+
+/// Every day's solution, ready to be looked up by the runner.
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register(day01::Day01);
+    registry.register(day02::Day02);
+    registry.register(day03::Day03);
+    registry.register(day04::Day04);
+    registry.register(day05::Day05);
+    registry.register(day06::Day06);
+    registry.register(day07::Day07);
+    registry.register(day08::Day08::<i64>::default());
+    registry.register(day09::Day09);
+    registry.register(day10::Day10);
+    registry.register(day11::Day11);
+    registry.register(day12::Day12);
+    registry.register(day13::Day13);
+    registry.register(day14::Day14);
+    registry.register(day15::Day15);
+    registry.register(day16::Day16::default());
+    registry.register(day17::Day17);
+    registry.register(day18::Day18);
+    registry.register(day19::Day19);
+    registry.register(day20::Day20);
+    registry.register(day21::Day21);
+    registry.register(day22::Day22);
+    registry.register(day23::Day23);
+    registry.register(day24::Day24);
+    registry.register(day25::Day25);
+    registry
+}
+
+/// The manifest of known-good answers used by `--verify`.
+pub fn answers_path() -> PathBuf {
+    common::crate_path("inputs/answers.toml")
+}
+
+#[test]
+fn test_answers_cover_registry() {
+    let answers = Answers::parse(&common::get_input(answers_path()).unwrap()).unwrap();
+    for solution in registry().iter().filter(|s| s.input_path().exists()) {
+        assert!(answers.get(solution.day(), 1).is_some(), "no part 1 answer for day {}", solution.day());
+        assert!(answers.get(solution.day(), 2).is_some(), "no part 2 answer for day {}", solution.day());
+    }
+}
+
+#[test]
+fn test_registry_days() {
+    let days: Vec<u32> = registry().iter().map(|s| s.day()).collect();
+    assert_eq!(days, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25]);
+}
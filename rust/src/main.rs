@@ -1,64 +1,323 @@
-use std::io::Write;
-use std::str::FromStr;
-
-extern crate regex;
-#[macro_use]
-extern crate lazy_static;
-
-mod common;
-mod coords;
-mod day01;
-mod day02;
-mod day03;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
+extern crate advent;
+extern crate num;
+
+use advent::answers::{Answers, Verdict};
+use advent::runner::{self, DayResult, InputSource, Selection};
+use advent::day08::Day08;
+use advent::day16::Day16;
+use advent::{common, error};
+use advent::error::AdventError;
+use std::io;
+#[cfg(test)]
+use std::path::PathBuf;
+
+/// How results should be printed: as prose and tables for people, or
+/// one record per line for scripts, either as JSON or `key=value` pairs.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    Human,
+    Json,
+    Plain,
+}
+
+/// Who dances on day 16, if not the usual sixteen programs: either
+/// the first few letters of the alphabet, or a lineup given by name.
+#[derive(Debug, PartialEq, Clone)]
+enum Lineup {
+    Dancers(usize),
+    Names(String),
+}
+
+/// Command-line options for the runner.
+#[derive(Debug, PartialEq)]
+struct Options {
+    selection: Selection,
+    bench: Option<usize>,
+    format: Format,
+    verify: bool,
+    input: InputSource,
+    lineup: Option<Lineup>,
+    bigint: bool,
+    trace: bool,
+}
+
+const DEFAULT_BENCH_ITERATIONS: usize = 10;
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let selection: Selection = match args.first() {
+            Some(arg) => arg.parse().map_err(|err| format!("could not parse day selection: {}", err))?,
+            None      => return Err("missing day selection".to_string()),
+        };
+        let mut options = Options {
+            selection, bench: None, format: Format::Human, verify: false, input: InputSource::Default, lineup: None, bigint: false, trace: false
+        };
+
+        let mut rest = args[1..].iter().peekable();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--bench" => {
+                    // The iteration count is optional
+                    let iterations = match rest.peek().and_then(|n| n.parse().ok()) {
+                        Some(n) => { rest.next(); n },
+                        None    => DEFAULT_BENCH_ITERATIONS,
+                    };
+                    if iterations == 0 {
+                        return Err("--bench needs at least one iteration".to_string());
+                    }
+                    options.bench = Some(iterations);
+                },
+                "--format" => {
+                    options.format = match rest.next().map(|f| f.as_str()) {
+                        Some("human") => Format::Human,
+                        Some("json")  => Format::Json,
+                        Some("plain") => Format::Plain,
+                        other         => return Err(format!("unknown format {:?}; expected human, json or plain", other)),
+                    };
+                },
+                "--verify" => options.verify = true,
+                "--input" => {
+                    let arg = rest.next().ok_or_else(|| "--input needs a file name, or - for stdin".to_string())?;
+                    options.input = InputSource::from(arg.as_str());
+                },
+                "--dancers" => {
+                    let count = rest.next().and_then(|n| n.parse().ok()).ok_or_else(|| "--dancers needs a number".to_string())?;
+                    options.lineup = Some(Lineup::Dancers(count));
+                },
+                "--lineup" => {
+                    let names = rest.next().ok_or_else(|| "--lineup needs the programs' names, like abcde".to_string())?;
+                    options.lineup = Some(Lineup::Names(names.to_string()));
+                },
+                "--bigint" => options.bigint = true,
+                "--trace" => options.trace = true,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        if options.input != InputSource::Default {
+            if !matches!(options.selection, Selection::Day(_)) {
+                return Err("--input can only be used with a single day".to_string());
+            }
+            if options.verify {
+                return Err("--verify checks the default inputs and can't be combined with --input".to_string());
+            }
+        }
+
+        if options.lineup.is_some() {
+            if options.selection != Selection::Day(16) {
+                return Err("--dancers and --lineup only apply to day 16".to_string());
+            }
+            if options.verify {
+                return Err("--verify checks the usual lineup and can't be combined with --dancers or --lineup".to_string());
+            }
+        }
+
+        if options.bigint && !options.selection.includes(8) {
+            return Err("--bigint only applies to day 8".to_string());
+        }
+
+        if options.trace && !options.selection.includes(8) {
+            return Err("--trace only applies to day 8".to_string());
+        }
+
+        if options.verify && options.bench.is_some() {
+            return Err("--verify can't be combined with --bench".to_string());
+        }
+
+        if options.format != Format::Human && options.verify {
+            return Err("--verify can only print human-readable output".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+#[test]
+fn test_options() {
+    let args = |s: &str| s.split_whitespace().map(|a| a.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(Options::parse(&args("8")), Ok(Options {
+        selection: Selection::Day(8), bench: None, format: Format::Human, verify: false, input: InputSource::Default, lineup: None, bigint: false, trace: false
+    }));
+    assert_eq!(Options::parse(&args("all --bench")), Ok(Options {
+        selection: Selection::All, bench: Some(DEFAULT_BENCH_ITERATIONS), format: Format::Human, verify: false, input: InputSource::Default, lineup: None, bigint: false, trace: false
+    }));
+    assert_eq!(Options::parse(&args("15 --bench 3 --format json")), Ok(Options {
+        selection: Selection::Day(15), bench: Some(3), format: Format::Json, verify: false, input: InputSource::Default, lineup: None, bigint: false, trace: false
+    }));
+    assert_eq!(Options::parse(&args("8-14 --verify")), Ok(Options {
+        selection: Selection::Range(8, 14), bench: None, format: Format::Human, verify: true, input: InputSource::Default, lineup: None, bigint: false, trace: false
+    }));
+    assert_eq!(Options::parse(&args("8 --input -")), Ok(Options {
+        selection: Selection::Day(8), bench: None, format: Format::Human, verify: false, input: InputSource::Stdin, lineup: None, bigint: false, trace: false
+    }));
+    assert_eq!(Options::parse(&args("8 --input other.txt --bench 2")), Ok(Options {
+        selection: Selection::Day(8), bench: Some(2), format: Format::Human, verify: false,
+        input: InputSource::File(PathBuf::from("other.txt")), lineup: None, bigint: false, trace: false
+    }));
+    assert!(Options::parse(&args("")).is_err());
+    assert!(Options::parse(&args("15 --bench 0")).is_err());
+    assert!(Options::parse(&args("15 --format xml")).is_err());
+    assert_eq!(Options::parse(&args("8-14 --format plain")), Ok(Options {
+        selection: Selection::Range(8, 14), bench: None, format: Format::Plain, verify: false, input: InputSource::Default, lineup: None, bigint: false, trace: false
+    }));
+    assert!(Options::parse(&args("15 --format json --verify")).is_err());
+    assert!(Options::parse(&args("15 --verify --bench")).is_err());
+    assert!(Options::parse(&args("15 --input")).is_err());
+    assert!(Options::parse(&args("all --input -")).is_err());
+    assert!(Options::parse(&args("15 --input - --verify")).is_err());
+    assert!(Options::parse(&args("15 --frobnicate")).is_err());
+    assert_eq!(Options::parse(&args("16 --dancers 5")).map(|o| o.lineup), Ok(Some(Lineup::Dancers(5))));
+    assert_eq!(Options::parse(&args("16 --lineup edcba --input -")).map(|o| o.lineup), Ok(Some(Lineup::Names("edcba".to_string()))));
+    assert!(Options::parse(&args("16 --dancers five")).is_err());
+    assert!(Options::parse(&args("15 --dancers 5")).is_err());
+    assert!(Options::parse(&args("all --lineup abc")).is_err());
+    assert!(Options::parse(&args("16 --lineup abc --verify")).is_err());
+    assert_eq!(Options::parse(&args("all --bigint --verify")).map(|o| o.bigint), Ok(true));
+    assert!(Options::parse(&args("9-12 --bigint")).is_err());
+    assert_eq!(Options::parse(&args("8 --trace --bigint")).map(|o| (o.trace, o.bigint)), Ok((true, true)));
+    assert!(Options::parse(&args("9 --trace")).is_err());
+}
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() < 2 {
-        writeln!(std::io::stderr(), "Usage: {} day_number [args...]", args[0]).unwrap();
+    let options = Options::parse(&args[1..]).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        eprintln!("Usage: {} day_number|first-last|all [--bench [iterations]] [--format human|json|plain] [--verify] [--input file|-] [--dancers n|--lineup names] [--bigint] [--trace]", args[0]);
         std::process::exit(1);
+    });
+
+    let mut registry = advent::registry();
+    if let Some(ref lineup) = options.lineup {
+        let day16 = match *lineup {
+            Lineup::Dancers(count)   => Day16::with_dancers(count),
+            Lineup::Names(ref names) => Day16::with_lineup(names),
+        };
+        registry.register(or_exit(day16.map_err(|err| err.on_day(16))));
+    }
+    match (options.bigint, options.trace) {
+        (true, true)   => registry.register(Day08::<num::BigInt>::traced()),
+        (true, false)  => registry.register(Day08::<num::BigInt>::default()),
+        (false, true)  => registry.register(Day08::<i64>::traced()),
+        (false, false) => {},
+    }
+    let selection = options.selection;
+
+    let solutions: Vec<_> = match selection {
+        Selection::Day(day) => registry.get(day).into_iter().collect(),
+        _                   => registry.iter().filter(|solution| selection.includes(solution.day())).collect(),
+    };
+    if solutions.is_empty() {
+        let days: Vec<String> = registry.iter().map(|s| s.day().to_string()).collect();
+        let requested = match selection {
+            Selection::Day(day) => format!("day {}", day),
+            _                   => format!("days {}", args[1]),
+        };
+        println!("Couldn't find implementation for {}; available days are {}", requested, days.join(", "));
+        std::process::exit(1);
+    }
+
+    // Not every day has its puzzle input checked in. Those days can still be
+    // run one at a time with --input, but are skipped when running several.
+    let solutions: Vec<_> = match selection {
+        Selection::Day(_) => solutions,
+        _                 => solutions.into_iter().filter(|solution| {
+            let path = solution.input_path();
+            if !path.exists() {
+                eprintln!("Skipping day {}: no input at {}", solution.day(), path.display());
+            }
+            path.exists()
+        }).collect(),
+    };
+
+    if let Some(iterations) = options.bench {
+        let results = or_exit(solutions.iter().map(|&solution| {
+            runner::load_input(solution, &options.input).and_then(|input| runner::bench(solution, &input, iterations))
+        }).collect::<error::Result<Vec<_>>>());
+        match options.format {
+            Format::Human => println!("{}", runner::format_bench_table(&results)),
+            Format::Json  => {
+                for result in results {
+                    println!("{}", result.to_json());
+                }
+            },
+            Format::Plain => {
+                for result in results {
+                    for line in result.to_key_values() {
+                        println!("{}", line);
+                    }
+                }
+            },
+        }
+        return;
     }
 
-    let day: u32 = u32::from_str(&args[1]).expect("could not parse day selection; must be a number");
-
-    let remaining = &args[2..];
-    let runner = match day {
-        1  => day01::run,
-        2  => day02::run,
-        3  => day03::run,
-        8  => day08::run,
-        9  => day09::run,
-        10 => day10::run,
-        11 => day11::run,
-        12 => day12::run,
-        13 => day13::run,
-        14 => day14::run,
-        15 => day15::run,
-        16 => day16::run,
-        17 => day17::run,
-        18 => day18::run,
-        19 => day19::run,
-        20 => day20::run,
-        21 => day21::run,
-        _  => {
-            println!("Couldn't find implementation for day {}", day);
+    let results = or_exit(solutions.iter().map(|&solution| {
+        runner::load_input(solution, &options.input).and_then(|input| runner::run(solution, &input))
+    }).collect::<error::Result<Vec<_>>>());
+
+    if options.verify {
+        let path = advent::answers_path();
+        let manifest = or_exit(common::get_input(&path).map_err(|err| {
+            AdventError::from(io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))
+        }));
+        let answers = or_exit(Answers::parse(&manifest).map_err(|err| AdventError::parse(err).context(path.display())));
+        if !verify(&results, &answers) {
             std::process::exit(1);
         }
-    };
+    } else if options.format != Format::Human {
+        for result in &results {
+            for record in result.records().iter() {
+                match options.format {
+                    Format::Json => println!("{}", record.to_json()),
+                    _            => println!("{}", record.to_key_values()),
+                }
+            }
+        }
+    } else if let Selection::Day(_) = selection {
+        let result = &results[0];
+        println!("Part 1: {}", result.part1.description);
+        println!("Part 2: {}", result.part2.description);
+    } else {
+        println!("{}", runner::format_table(&results));
+    }
+}
+
+/// Unwraps a result, or reports the error and exits if there was one.
+fn or_exit<T>(result: error::Result<T>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    })
+}
+
+/// Checks every answer against the manifest, printing the outcome of each.
+/// Returns `false` if any answer was wrong.
+fn verify(results: &[DayResult], answers: &Answers) -> bool {
+    let (mut correct, mut incorrect, mut unknown) = (0, 0, 0);
+
+    for result in results {
+        for &(part, answer) in [(1, &result.part1), (2, &result.part2)].iter() {
+            match answers.check(result.day, part, &answer.value) {
+                Verdict::Correct => {
+                    correct += 1;
+                    println!("Day {} part {}: ok", result.day, part);
+                },
+                Verdict::Incorrect { expected } => {
+                    incorrect += 1;
+                    println!("Day {} part {}: MISMATCH", result.day, part);
+                    println!("  - {}", expected);
+                    println!("  + {}", answer.value);
+                },
+                Verdict::Unknown => {
+                    unknown += 1;
+                    println!("Day {} part {}: no known answer (got {})", result.day, part, answer.value);
+                },
+            }
+        }
+    }
 
-    runner(remaining);
+    println!("{} correct, {} incorrect, {} unknown", correct, incorrect, unknown);
+    incorrect == 0
 }
@@ -0,0 +1,353 @@
+use common;
+use error::{AdventError, Result};
+use solution::{Answer, AnySolution};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Which days to run, as given on the command line: a single day
+/// (`8`), an inclusive range of days (`8-14`), or `all` of them.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Selection {
+    Day(u32),
+    Range(u32, u32),
+    All,
+}
+
+impl Selection {
+    pub fn includes(&self, day: u32) -> bool {
+        match *self {
+            Selection::Day(d)              => d == day,
+            Selection::Range(first, last)  => first <= day && day <= last,
+            Selection::All                 => true,
+        }
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        let parse_day = |d: &str| {
+            u32::from_str(d.trim()).map_err(|_| format!("couldn't parse day '{}'; must be a number", d))
+        };
+
+        if s == "all" {
+            return Ok(Selection::All);
+        }
+        if s.starts_with('-') {
+            return Err(format!("expected a day, a range of days like 8-14, or all, but found '{}'", s));
+        }
+
+        let mut parts = s.splitn(2, '-');
+        let first = parts.next().unwrap_or("");
+        match parts.next() {
+            Some(last) => {
+                if last.trim().is_empty() {
+                    return Err(format!("day range {} is missing its last day", s));
+                }
+                let (first, last) = (parse_day(first)?, parse_day(last)?);
+                if first > last {
+                    return Err(format!("day range {} is backwards", s));
+                }
+                Ok(Selection::Range(first, last))
+            },
+            None => parse_day(first).map(Selection::Day),
+        }
+    }
+}
+
+#[test]
+fn test_selection() {
+    assert_eq!("all".parse(), Ok(Selection::All));
+    assert_eq!("8".parse(), Ok(Selection::Day(8)));
+    assert_eq!("8-14".parse(), Ok(Selection::Range(8, 14)));
+    assert!("14-8".parse::<Selection>().is_err());
+    assert!("eight".parse::<Selection>().is_err());
+    assert_eq!("8-".parse::<Selection>(), Err("day range 8- is missing its last day".to_string()));
+    assert_eq!("--help".parse::<Selection>(), Err("expected a day, a range of days like 8-14, or all, but found '--help'".to_string()));
+    assert!("-3".parse::<Selection>().is_err());
+    assert!("8-9-10".parse::<Selection>().is_err());
+
+    assert!(Selection::Range(8, 14).includes(8));
+    assert!(Selection::Range(8, 14).includes(14));
+    assert!(!Selection::Range(8, 14).includes(15));
+    assert!(!Selection::Day(3).includes(4));
+}
+
+/// The answers to both parts of a day's puzzle, and how long each step took.
+pub struct DayResult {
+    pub day: u32,
+    pub parse_time: Duration,
+    pub part1: Answer,
+    pub part1_time: Duration,
+    pub part2: Answer,
+    pub part2_time: Duration,
+}
+
+fn timed<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Where to read a day's puzzle input from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum InputSource {
+    /// The day's own input file under `inputs/`.
+    Default,
+    File(PathBuf),
+    Stdin,
+}
+
+impl<'a> From<&'a str> for InputSource {
+    /// Interprets a command-line argument, where `-` means stdin.
+    fn from(arg: &'a str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            _   => InputSource::File(PathBuf::from(arg)),
+        }
+    }
+}
+
+/// Loads the input for a solution.
+pub fn load_input(solution: &dyn AnySolution, source: &InputSource) -> Result<String> {
+    let input = match *source {
+        InputSource::Default        => common::get_input(solution.input_path()),
+        InputSource::File(ref path) => common::get_input(path),
+        InputSource::Stdin          => common::get_stdin(),
+    };
+
+    input.map_err(|err| AdventError::from(err).on_day(solution.day()))
+}
+
+/// Runs both parts of a solution against the given input, timing each step.
+/// Stops at the first step that fails.
+pub fn run(solution: &dyn AnySolution, input: &str) -> Result<DayResult> {
+    let (parsed, parse_time) = timed(|| solution.parse(input));
+    let parsed = parsed?;
+    let (part1, part1_time) = timed(|| solution.part1(parsed.as_ref()));
+    let part1 = part1?;
+    let (part2, part2_time) = timed(|| solution.part2(parsed.as_ref()));
+
+    Ok(DayResult { day: solution.day(), parse_time, part1, part1_time, part2: part2?, part2_time })
+}
+
+impl DayResult {
+    /// One record for each part's answer.
+    pub fn records(&self) -> [AnswerRecord<'_>; 2] {
+        [
+            AnswerRecord { day: self.day, part: 1, answer: &self.part1.value, elapsed: self.part1_time },
+            AnswerRecord { day: self.day, part: 2, answer: &self.part2.value, elapsed: self.part2_time },
+        ]
+    }
+}
+
+/// The answer to one part of a day's puzzle, as printed by the
+/// machine-readable output formats. `elapsed` is in nanoseconds.
+#[derive(Debug, PartialEq)]
+pub struct AnswerRecord<'a> {
+    pub day: u32,
+    pub part: u8,
+    pub answer: &'a str,
+    pub elapsed: Duration,
+}
+
+impl<'a> AnswerRecord<'a> {
+    pub fn to_json(&self) -> String {
+        format!("{{\"day\":{},\"part\":{},\"answer\":{},\"elapsed\":{}}}",
+                self.day, self.part, json_string(self.answer), self.elapsed.as_nanos())
+    }
+
+    pub fn to_key_values(&self) -> String {
+        format!("day={} part={} answer={} elapsed={}", self.day, self.part, self.answer, self.elapsed.as_nanos())
+    }
+}
+
+/// Quotes a string for JSON output.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"'  => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c    => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn test_answer_records() {
+    let record = AnswerRecord { day: 16, part: 1, answer: "fnloekigdmpajchb", elapsed: Duration::from_micros(1500) };
+    assert_eq!(record.to_json(), r#"{"day":16,"part":1,"answer":"fnloekigdmpajchb","elapsed":1500000}"#);
+    assert_eq!(record.to_key_values(), "day=16 part=1 answer=fnloekigdmpajchb elapsed=1500000");
+    assert_eq!(json_string("a \"b\"\\\n"), r#""a \"b\"\\\n""#);
+}
+
+/// Formats results as a table with one row per day.
+pub fn format_table(results: &[DayResult]) -> String {
+    let header = ["Day", "Parse", "Part 1", "Time", "Part 2", "Time"];
+    let rows: Vec<Vec<String>> = results.iter().map(|r| vec![
+        r.day.to_string(),
+        format!("{:.2?}", r.parse_time),
+        r.part1.value.clone(),
+        format!("{:.2?}", r.part1_time),
+        r.part2.value.clone(),
+        format!("{:.2?}", r.part2_time),
+    ]).collect();
+
+    tabulate(&header, rows)
+}
+
+/// Lays out rows of cells in aligned columns beneath a header.
+fn tabulate(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let widths: Vec<usize> = (0..header.len()).map(|col| {
+        rows.iter().map(|row| row[col].len()).chain(Some(header[col].len())).max().unwrap_or(0)
+    }).collect();
+
+    let format_row = |cells: Vec<String>| {
+        let padded: Vec<String> = cells.iter().zip(widths.iter()).map(|(cell, &width)| {
+            format!("{:<width$}", cell, width = width)
+        }).collect();
+        padded.join(" | ").trim_end().to_string()
+    };
+
+    let mut lines = vec![format_row(header.iter().map(|h| h.to_string()).collect())];
+    lines.push(widths.iter().map(|&w| "-".repeat(w)).collect::<Vec<_>>().join("-+-"));
+    lines.extend(rows.into_iter().map(format_row));
+    lines.join("\n")
+}
+
+#[test]
+fn test_format_table() {
+    let answer = |value: &str| Answer { value: value.to_string(), description: String::new() };
+    let results = vec![
+        DayResult {
+            day: 1,
+            parse_time: Duration::from_millis(0),
+            part1: answer("1047"),
+            part1_time: Duration::from_millis(1),
+            part2: answer("982"),
+            part2_time: Duration::from_millis(2),
+        },
+        DayResult {
+            day: 10,
+            parse_time: Duration::from_millis(0),
+            part1: answer("7888"),
+            part1_time: Duration::from_millis(3),
+            part2: answer("decdf7d377879877173b7f2fb131cf1b"),
+            part2_time: Duration::from_millis(40),
+        },
+    ];
+
+    assert_eq!(format_table(&results), "\
+Day | Parse  | Part 1 | Time   | Part 2                           | Time
+----+--------+--------+--------+----------------------------------+--------
+1   | 0.00ns | 1047   | 1.00ms | 982                              | 2.00ms
+10  | 0.00ns | 7888   | 3.00ms | decdf7d377879877173b7f2fb131cf1b | 40.00ms");
+}
+
+/// The spread of timings for one step of a solution over several runs.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "can't compute stats without samples");
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len() % 2 == 1 {
+            samples[mid]
+        } else {
+            (samples[mid - 1] + samples[mid]) / 2
+        };
+
+        Stats { min: samples[0], median, max: samples[samples.len() - 1] }
+    }
+
+    fn to_json(&self) -> String {
+        format!("{{\"min_ns\":{},\"median_ns\":{},\"max_ns\":{}}}",
+                self.min.as_nanos(), self.median.as_nanos(), self.max.as_nanos())
+    }
+}
+
+#[test]
+fn test_stats() {
+    let ms = Duration::from_millis;
+    assert_eq!(Stats::from_samples(vec![ms(5), ms(1), ms(3)]), Stats { min: ms(1), median: ms(3), max: ms(5) });
+    assert_eq!(Stats::from_samples(vec![ms(8), ms(1), ms(2), ms(4)]), Stats { min: ms(1), median: ms(3), max: ms(8) });
+    assert_eq!(Stats::from_samples(vec![ms(1)]).to_json(), r#"{"min_ns":1000000,"median_ns":1000000,"max_ns":1000000}"#);
+}
+
+/// Timings for each step of a solution, run repeatedly.
+pub struct BenchResult {
+    pub day: u32,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl BenchResult {
+    pub fn to_json(&self) -> String {
+        format!("{{\"day\":{},\"iterations\":{},\"parse\":{},\"part1\":{},\"part2\":{}}}",
+                self.day, self.iterations, self.parse.to_json(), self.part1.to_json(), self.part2.to_json())
+    }
+
+    /// One line of `key=value` pairs for each step.
+    pub fn to_key_values(&self) -> Vec<String> {
+        [("parse", &self.parse), ("part1", &self.part1), ("part2", &self.part2)].iter().map(|&(step, stats)| {
+            format!("day={} step={} iterations={} min_ns={} median_ns={} max_ns={}",
+                    self.day, step, self.iterations,
+                    stats.min.as_nanos(), stats.median.as_nanos(), stats.max.as_nanos())
+        }).collect()
+    }
+}
+
+/// Runs each step of a solution `iterations` times. Both parts are
+/// run against the same parsed input. The solution is run once up
+/// front so that failures are reported rather than timed.
+pub fn bench(solution: &dyn AnySolution, input: &str, iterations: usize) -> Result<BenchResult> {
+    run(solution, input)?;
+    let parsed = solution.parse(input)?;
+    let sample = |f: &dyn Fn()| {
+        Stats::from_samples((0..iterations).map(|_| timed(f).1).collect())
+    };
+
+    Ok(BenchResult {
+        day: solution.day(),
+        iterations,
+        parse: sample(&|| { let _ = solution.parse(input); }),
+        part1: sample(&|| { let _ = solution.part1(parsed.as_ref()); }),
+        part2: sample(&|| { let _ = solution.part2(parsed.as_ref()); }),
+    })
+}
+
+/// Formats benchmark results as a table with one row per step of each day.
+pub fn format_bench_table(results: &[BenchResult]) -> String {
+    let header = ["Day", "Step", "Runs", "Min", "Median", "Max"];
+    let mut rows = vec![];
+    for r in results {
+        for &(step, stats) in [("parse", &r.parse), ("part 1", &r.part1), ("part 2", &r.part2)].iter() {
+            rows.push(vec![
+                r.day.to_string(),
+                step.to_string(),
+                r.iterations.to_string(),
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.max),
+            ]);
+        }
+    }
+
+    tabulate(&header, rows)
+}
@@ -0,0 +1,162 @@
+use common;
+use error::Result;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// A solution to a single day's puzzle.
+///
+/// Input is parsed once with `parse`, and the result is handed to
+/// `part1` and `part2`, which return their answers as typed values.
+/// Errors don't need to say which day they came from; the runner
+/// fills that in.
+pub trait Solution {
+    /// The day of December this solution is for.
+    const DAY: u32;
+
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1>;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2>;
+
+    /// Path of the puzzle input used when no other input is given.
+    fn input_path(&self) -> PathBuf {
+        common::crate_path(&format!("inputs/{:02}.txt", Self::DAY))
+    }
+
+    /// A human-friendly description of the Part 1 answer.
+    fn describe_part1(&self, answer: &Self::Part1) -> String {
+        answer.to_string()
+    }
+
+    /// A human-friendly description of the Part 2 answer.
+    fn describe_part2(&self, answer: &Self::Part2) -> String {
+        answer.to_string()
+    }
+}
+
+/// An answer to one part of a puzzle, with the typed value
+/// already formatted for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub value: String,
+    pub description: String,
+}
+
+/// A type-erased `Solution`, so that solutions with different
+/// input and answer types can be stored side by side in a `Registry`.
+pub trait AnySolution {
+    fn day(&self) -> u32;
+    fn input_path(&self) -> PathBuf;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn part1(&self, input: &dyn Any) -> Result<Answer>;
+    fn part2(&self, input: &dyn Any) -> Result<Answer>;
+}
+
+impl<S> AnySolution for S where S: Solution, S::Input: 'static {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn input_path(&self) -> PathBuf {
+        Solution::input_path(self)
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        let parsed = Solution::parse(self, input).map_err(|err| err.on_day(S::DAY))?;
+        Ok(Box::new(parsed))
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer> {
+        let input = input.downcast_ref::<S::Input>().expect("input was not parsed by this solution");
+        let answer = Solution::part1(self, input).map_err(|err| err.on_day(S::DAY))?;
+        Ok(Answer { value: answer.to_string(), description: self.describe_part1(&answer) })
+    }
+
+    fn part2(&self, input: &dyn Any) -> Result<Answer> {
+        let input = input.downcast_ref::<S::Input>().expect("input was not parsed by this solution");
+        let answer = Solution::part2(self, input).map_err(|err| err.on_day(S::DAY))?;
+        Ok(Answer { value: answer.to_string(), description: self.describe_part2(&answer) })
+    }
+}
+
+/// All the solutions known to the runner, keyed by day.
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<u32, Box<dyn AnySolution>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry { solutions: BTreeMap::new() }
+    }
+
+    pub fn register<S>(&mut self, solution: S) where S: AnySolution + 'static {
+        self.solutions.insert(solution.day(), Box::new(solution));
+    }
+
+    pub fn get(&self, day: u32) -> Option<&dyn AnySolution> {
+        self.solutions.get(&day).map(|s| s.as_ref())
+    }
+
+    /// Iterates over the registered solutions in order of day.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn AnySolution> {
+        self.solutions.values().map(|s| s.as_ref())
+    }
+}
+
+#[cfg(test)]
+use error::AdventError;
+
+#[cfg(test)]
+struct Doubler;
+
+#[cfg(test)]
+impl Solution for Doubler {
+    const DAY: u32 = 26;
+
+    type Input = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>> {
+        input.split(',').map(|s| s.parse().map_err(|_| AdventError::parse("not a number"))).collect()
+    }
+
+    fn part1(&self, input: &Vec<u32>) -> Result<u32> {
+        Ok(input.iter().sum::<u32>() * 2)
+    }
+
+    fn part2(&self, input: &Vec<u32>) -> Result<u32> {
+        input.iter().max().map(|max| max * 2).ok_or_else(|| AdventError::solve("no numbers"))
+    }
+
+    fn describe_part2(&self, answer: &u32) -> String {
+        format!("{} is the biggest", answer)
+    }
+}
+
+#[test]
+fn test_registry() {
+    let mut registry = Registry::new();
+    registry.register(Doubler);
+    assert!(registry.get(1).is_none());
+    assert_eq!(registry.iter().map(|s| s.day()).collect::<Vec<_>>(), vec![26]);
+
+    let solution = registry.get(26).unwrap();
+    assert_eq!(solution.input_path(), common::crate_path("inputs/26.txt"));
+    let input = solution.parse("1,5,3").unwrap();
+    assert_eq!(solution.part1(input.as_ref()).unwrap(), Answer {
+        value: "18".to_string(),
+        description: "18".to_string(),
+    });
+    assert_eq!(solution.part2(input.as_ref()).unwrap(), Answer {
+        value: "10".to_string(),
+        description: "10 is the biggest".to_string(),
+    });
+    assert_eq!(solution.parse("1,x").unwrap_err().day, Some(26));
+}